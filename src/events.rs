@@ -2,6 +2,7 @@ use std::marker::PhantomData;
 use std::default::Default;
 use sdl2::EventPump;
 pub use sdl2::event::Event;
+use sdl2::event::WindowEvent;
 
 pub trait KeySet: Default {
     fn from_keycode_iterator<T: Iterator<Item=Event>>(&self, _: T) -> Self;
@@ -19,6 +20,18 @@ key_set! {
             right: Right,
             fire: Space | Return,
             next_weapon: PageUp,
+            prev_weapon: PageDown,
+            weapon_1: Num1,
+            weapon_2: Num2,
+            weapon_3: Num3,
+            weapon_4: Num4,
+            weapon_5: Num5,
+            shield: LShift | Z,
+            bomb: LCtrl | X,
+            fullscreen: F11,
+            draw_stats: F3,
+            screenshot: F12,
+            record: F9,
         },
         else: {
             quit: Quit { .. },
@@ -28,6 +41,7 @@ key_set! {
 
 pub struct EventStream<T: KeySet> {
     pump: EventPump,
+    resized: bool,
     _out: PhantomData<T>,
 }
 
@@ -41,11 +55,32 @@ impl<T: KeySet> EventStream<T> {
     pub fn new(pump: EventPump) -> EventStream<T> {
         EventStream {
             pump: pump,
+            resized: false,
             _out: PhantomData,
         }
    }
 
     pub fn pump(&mut self, last: &T) -> T {
-        last.from_keycode_iterator(self.pump.poll_iter())
+        let resized = &mut self.resized;
+
+        last.from_keycode_iterator(
+            self.pump.poll_iter().inspect(|e|
+                match *e {
+                    Event::Window {
+                        win_event: WindowEvent::Resized(..), ..
+                    } |
+                    Event::Window {
+                        win_event: WindowEvent::SizeChanged(..), ..
+                    } => *resized = true,
+                    _ => {},
+                }
+            )
+        )
+    }
+
+    /// Whether the window has changed size since this was last called, in
+    /// which case whatever depends on its size needs working out again.
+    pub fn take_resized(&mut self) -> bool {
+        ::std::mem::replace(&mut self.resized, false)
     }
 }
@@ -9,9 +9,13 @@ pub struct ParallaxSet<
     Time: TimeExtensions,
     T: GetSize,
     I
-> where for<'a> &'a I: IntoIterator<Item=&'a ([f64; 2], [f64; 2], Sprite<T>)> {
+> where for<'a> &'a I: IntoIterator<Item=&'a (Vec2, Vec2, Sprite<T>)> {
     pub start_time: Time,
     pub sprites: I,
+    /// When scrolling was stopped, in seconds, if it currently is.
+    paused_at: Option<f64>,
+    /// The total number of seconds spent paused before `paused_at`.
+    paused_for: f64,
 }
 
 impl<
@@ -19,23 +23,49 @@ impl<
     T: GetSize,
     I
 > ParallaxSet<Time, T, I> where
-    for<'a> &'a I: IntoIterator<Item=&'a ([f64; 2], [f64; 2], Sprite<T>)>
+    for<'a> &'a I: IntoIterator<Item=&'a (Vec2, Vec2, Sprite<T>)>
 {
     pub fn new(sprites: I, start: Time) -> Self {
         ParallaxSet {
             sprites: sprites,
             start_time: start,
+            paused_at: None,
+            paused_for: 0.0,
         }
     }
 
+    /// Stops the set scrolling, until `resume` is called.
+    pub fn pause(&mut self, now: Time) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(now.exact_seconds());
+        }
+    }
+
+    pub fn resume(&mut self, now: Time) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_for += now.exact_seconds() - paused_at;
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// How many seconds the set has spent scrolling by `now`.
+    fn scrolled_for(&self, now: Time) -> f64 {
+        self.paused_at.unwrap_or(now.exact_seconds()) -
+            self.start_time.exact_seconds() -
+            self.paused_for
+    }
+
     pub fn get_offsets(&self, now: Time) -> Vec<(&Sprite<T>, [i32; 2])> {
-        let dt = now.exact_seconds() - self.start_time.exact_seconds();
+        let dt = self.scrolled_for(now);
 
         self.sprites.into_iter().map(
-            |&([vx, vy], [x, y], ref s)| {
-                let (dx, dy) = (dt * vx, dt * vy);
+            |&(velocity, position, ref s)| {
+                let Vec2 { x, y } = position + velocity * dt;
 
-                (s, [(x + dx) as _, (y + dy) as _])
+                (s, [x as _, y as _])
             }
         ).collect()
     }
@@ -45,15 +75,15 @@ impl<
         screen: Dest,
         now: Time
     ) -> Vec<(Sprite<T>, Dest)> {
-        let dt = now.exact_seconds() - self.start_time.exact_seconds();
+        let dt = self.scrolled_for(now);
 
         let (screen_w, screen_h) = (screen.width, screen.height);
 
         self.sprites.into_iter().map(
-            |&([vx, vy], [x, y], ref s)| {
-                let (dx, dy) = (dt * vx, dt * vy);
+            |&(velocity, position, ref s)| {
+                let Vec2 { x, y } = position + velocity * dt;
 
-                (s, [(x + dx) as _, (y + dy) as _])
+                (s, [x as _, y as _])
             }
         ).flat_map(|(spr, [offset_x, offset_y])| {
             let (spr_w, spr_h) = (spr.mask.width, spr.mask.height);
@@ -0,0 +1,95 @@
+use ::gameobjects::handle::Handle;
+
+/// Groups of events that objects can subscribe to, so that they don't have to
+/// be told about everything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Topic {
+    Player,
+    Boss,
+    Pickup,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    PlayerDied {
+        lives_left: u32,
+    },
+    BossPhaseChanged {
+        phase: usize,
+    },
+    /// Sent by a part of a boss to the boss itself, so that it can keep track
+    /// of its total health.
+    BossDamaged {
+        damage: u32,
+    },
+    BossDefeated,
+    PickupCollected,
+    /// Sent to everything when the player uses a bomb.
+    BombDetonated,
+}
+
+impl GameEvent {
+    pub fn topic(&self) -> Topic {
+        use self::GameEvent::*;
+
+        match *self {
+            PlayerDied { .. } | BombDetonated => Topic::Player,
+            BossPhaseChanged { .. } |
+            BossDamaged { .. } |
+            BossDefeated => Topic::Boss,
+            PickupCollected => Topic::Pickup,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Recipients {
+    All,
+    Subscribers(Topic),
+    Object(Handle),
+}
+
+/// An event on its way to other objects. These are delivered at the start of
+/// the tick after they were sent, before any object has updated.
+#[derive(Clone, Debug)]
+pub struct Broadcast {
+    pub recipients: Recipients,
+    pub event: GameEvent,
+    /// Filled in by the view with the handle of the object that sent it.
+    pub sender: Option<Handle>,
+}
+
+impl Broadcast {
+    pub fn new(recipients: Recipients, event: GameEvent) -> Self {
+        Broadcast {
+            recipients: recipients,
+            event: event,
+            sender: None,
+        }
+    }
+
+    pub fn to_all(event: GameEvent) -> Self {
+        Self::new(Recipients::All, event)
+    }
+
+    /// Sends `event` to everything subscribed to its topic.
+    pub fn to_subscribers(event: GameEvent) -> Self {
+        Self::new(Recipients::Subscribers(event.topic()), event)
+    }
+
+    pub fn to_object(handle: Handle, event: GameEvent) -> Self {
+        Self::new(Recipients::Object(handle), event)
+    }
+
+    pub fn is_for<F: FnOnce(Topic) -> bool>(
+        &self,
+        handle: Handle,
+        is_subscribed: F
+    ) -> bool {
+        match self.recipients {
+            Recipients::All => true,
+            Recipients::Subscribers(topic) => is_subscribed(topic),
+            Recipients::Object(h) => h == handle,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use ::gameobjects::*;
+use ::gameobjects::handle::Handle;
+use ::gameobjects::object_table::ObjectTable;
+use ::events::GameTime;
+use ::time::*;
+
+/// The size of the area that gameplay happens in, in world units. This
+/// doesn't change with the size of the window.
+pub const PLAY_AREA_SIZE: [f64; 2] = [800.0, 600.0];
+
+/// The play area, with its top left corner at the origin.
+pub fn play_area() -> BoundingRect {
+    BoundingRect::default().with_size(PLAY_AREA_SIZE[0], PLAY_AREA_SIZE[1])
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraMode {
+    /// Stays where it's put.
+    Fixed,
+    /// Moves at a constant velocity, in world units per second.
+    Scroll(Vec2),
+    /// Eases towards the center of an object, closing `stiffness` of the gap
+    /// each second.
+    Follow {
+        target: Handle,
+        stiffness: f64,
+    },
+}
+
+/// Decides which part of the world is on the screen. Objects draw themselves
+/// in world coordinates, and the view converts those to the screen with
+/// `to_screen` when it renders them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    /// The point in the world at the center of the screen.
+    pub center: Vec2,
+    /// How many pixels one world unit covers.
+    pub zoom: f64,
+    /// The size of the screen, in pixels.
+    pub viewport: Vec2,
+    pub mode: CameraMode,
+    /// The visible area is kept inside this, when there is one.
+    pub limits: Option<BoundingRect>,
+}
+
+impl Camera {
+    /// A fixed camera zoomed so that all of `area` just fits on the screen.
+    pub fn looking_at(area: BoundingRect, viewport: Vec2) -> Self {
+        use ::gameobjects::MinMax;
+
+        Camera {
+            center: area.center(),
+            zoom: (viewport.x / area.width).min(viewport.y / area.height),
+            viewport: viewport,
+            mode: CameraMode::Fixed,
+            limits: None,
+        }
+    }
+
+    /// The part of the world that's on the screen.
+    pub fn visible_area(&self) -> BoundingRect {
+        let size = self.viewport / self.zoom;
+
+        BoundingRect::default()
+            .with_size(size.x, size.y)
+            .centered_at(self.center)
+    }
+
+    pub fn to_screen(&self, point: Vec2) -> Vec2 {
+        (point - self.center) * self.zoom + self.viewport / 2.0
+    }
+
+    pub fn to_world(&self, point: Vec2) -> Vec2 {
+        (point - self.viewport / 2.0) / self.zoom + self.center
+    }
+
+    /// Where `dest`, given in world coordinates, is drawn on the screen.
+    pub fn project(&self, dest: Dest) -> Dest {
+        let top_left = self.to_screen(Vec2::new(dest.x as _, dest.y as _));
+
+        Dest {
+            x: top_left.x.round() as _,
+            y: top_left.y.round() as _,
+            width: (dest.width as f64 * self.zoom).round() as _,
+            height: (dest.height as f64 * self.zoom).round() as _,
+        }
+    }
+
+    /// Moves the camera on by `time.elapsed`. Followed objects are looked up
+    /// in `objects`, and the camera stays put once they're gone.
+    pub fn update(&mut self, objects: &ObjectTable, time: GameTime) {
+        let dt = time.elapsed.exact_seconds();
+
+        match self.mode {
+            CameraMode::Fixed => {},
+            CameraMode::Scroll(velocity) => self.center += velocity * dt,
+            CameraMode::Follow { target, stiffness } => {
+                let target = objects.get(target)
+                    .and_then(|info| info.bounds)
+                    .map(|b| b.center());
+
+                if let Some(target) = target {
+                    self.center = self.center.lerp(
+                        target,
+                        (stiffness * dt).min(1.0)
+                    );
+                }
+            },
+        }
+
+        if let Some(limits) = self.limits {
+            self.center = self.limited(limits);
+        }
+    }
+
+    /// The nearest center to the current one that keeps the visible area
+    /// inside `limits`. If the visible area is bigger than `limits`, it's
+    /// centered on them instead.
+    fn limited(&self, limits: BoundingRect) -> Vec2 {
+        use ::gameobjects::MinMax;
+
+        let half = self.viewport / self.zoom / 2.0;
+        let middle = limits.center();
+
+        let clamp = |value: f64, half: f64, min: f64, max: f64, mid: f64|
+            if max - min < half * 2.0 {
+                mid
+            } else {
+                value.limit(min + half, max - half)
+            };
+
+        Vec2::new(
+            clamp(
+                self.center.x,
+                half.x,
+                limits.left(),
+                limits.right(),
+                middle.x
+            ),
+            clamp(
+                self.center.y,
+                half.y,
+                limits.top(),
+                limits.bottom(),
+                middle.y
+            ),
+        )
+    }
+}
@@ -0,0 +1,567 @@
+//! An entity-component-system world that runs alongside the boxed
+//! `GameObject`s of a view. Entities are just handles; everything they do
+//! comes from which components they have and the systems run over them, so
+//! behaviour like moving, expiring and exploding is written once here instead
+//! of in every object type.
+
+use ::gameobjects::*;
+use ::gameobjects::handle::{Handle, HandleAllocator};
+use ::gameobjects::object_table::ObjectKind;
+use ::gameobjects::broadcast::GameEvent;
+use ::gameobjects::player::{
+    GameObject,
+    GameAction,
+    GameMessage,
+    DamageInfo,
+    DamageFilter,
+    Explosion,
+    SimpleObject,
+    bounds_dest,
+};
+use ::graphics::sprites::{VisibleComponent, VisibleRect};
+use ::graphics::backend::Texture;
+use ::events::{Keys, GameTime};
+use ::view::Context;
+use ::set::Intersects;
+use ::time::TimeExtensions;
+
+use std::convert::TryInto;
+use sdl2::pixels::Color;
+
+pub type Entity = Handle;
+
+/// Component storage, indexed by entity. Only the `World` knows which entities
+/// are alive, so lookups should go through it when handles might be stale.
+pub struct Components<T> {
+    items: Vec<Option<T>>,
+}
+
+impl<T> Default for Components<T> {
+    fn default() -> Self {
+        Components { items: vec![] }
+    }
+}
+
+impl<T> Components<T> {
+    pub fn insert(&mut self, entity: Entity, component: T) {
+        let i = entity.index();
+
+        while self.items.len() <= i {
+            self.items.push(None);
+        }
+
+        self.items[i] = Some(component);
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        self.items.get_mut(entity.index()).and_then(|c| c.take())
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        self.items.get(entity.index()).and_then(|c| c.as_ref())
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        self.items.get_mut(entity.index()).and_then(|c| c.as_mut())
+    }
+}
+
+/// Something that can hit and be hit. `shape` is relative to the entity's
+/// position.
+#[derive(Clone, Copy, Debug)]
+pub struct Collider {
+    pub shape: Bounds,
+    pub team: DamageFilter,
+    pub damage: u32,
+    pub kind: ObjectKind,
+    /// Whether the entity is removed after hitting anything not on its team,
+    /// like a bullet. This works whether or not it has any `Health`.
+    pub despawn_on_hit: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Health {
+    pub hp: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Lifetime {
+    pub expires_at: u32,
+}
+
+/// Speeds an entity up along `direction`, or slows it down if `rate` is
+/// negative, keeping its speed between zero and `max_speed`. The direction
+/// is kept separately so that an entity that's slowed to a stop still knows
+/// which way to speed up again.
+#[derive(Clone, Copy, Debug)]
+pub struct Acceleration {
+    pub direction: Vec2,
+    pub rate: f64,
+    pub max_speed: f64,
+}
+
+/// A flat-coloured box, which is all that the entities so far need.
+#[derive(Clone, Copy, Debug)]
+pub struct Appearance {
+    pub color: Color,
+    pub size: Vec2,
+}
+
+/// An entity that hasn't been spawned yet. Objects can't get at the `World`
+/// themselves, so they describe the entities they want with these and hand
+/// them back in a `GameAction::SpawnEntities`.
+#[derive(Clone, Debug, Default)]
+pub struct EntitySpec {
+    pub position: Vec2,
+    pub velocity: Option<Vec2>,
+    pub acceleration: Option<Acceleration>,
+    pub launch_at: Option<u32>,
+    pub collider: Option<Collider>,
+    pub appearance: Option<Appearance>,
+    pub health: Option<Health>,
+    pub explosion: Option<Vec2>,
+    pub lifetime: Option<Lifetime>,
+    pub despawn_offscreen: bool,
+}
+
+impl EntitySpec {
+    pub fn at(position: Vec2) -> Self {
+        EntitySpec {
+            position: position,
+            .. Default::default()
+        }
+    }
+
+    pub fn velocity(self, velocity: Vec2) -> Self {
+        EntitySpec { velocity: Some(velocity), .. self }
+    }
+
+    pub fn acceleration(self, acceleration: Acceleration) -> Self {
+        EntitySpec { acceleration: Some(acceleration), .. self }
+    }
+
+    /// Keeps the entity where it is until `time`.
+    pub fn launch_at(self, time: u32) -> Self {
+        EntitySpec { launch_at: Some(time), .. self }
+    }
+
+    pub fn collider(self, collider: Collider) -> Self {
+        EntitySpec { collider: Some(collider), .. self }
+    }
+
+    pub fn appearance(self, color: Color, size: Vec2) -> Self {
+        EntitySpec {
+            appearance: Some(
+                Appearance {
+                    color: color,
+                    size: size,
+                }
+            ),
+            .. self
+        }
+    }
+
+    /// Leaves an explosion `size` across behind when the entity dies.
+    pub fn explodes(self, size: Vec2) -> Self {
+        EntitySpec { explosion: Some(size), .. self }
+    }
+
+    pub fn despawn_offscreen(self) -> Self {
+        EntitySpec { despawn_offscreen: true, .. self }
+    }
+}
+
+#[derive(Default)]
+pub struct World {
+    entities: HandleAllocator,
+    live: Vec<Entity>,
+    /// Entities that hit something this tick and are about to be despawned
+    /// for it, whatever their health.
+    killed: Vec<Entity>,
+    pub positions: Components<Vec2>,
+    pub velocities: Components<Vec2>,
+    pub accelerations: Components<Acceleration>,
+    /// Entities that hang in place until the given time.
+    pub launches: Components<u32>,
+    pub colliders: Components<Collider>,
+    pub appearances: Components<Appearance>,
+    pub health: Components<Health>,
+    /// How big an explosion entities leave behind when they die.
+    pub explosions: Components<Vec2>,
+    pub lifetimes: Components<Lifetime>,
+    /// Entities that are removed once they leave the play area, and whether
+    /// they've entered it yet (so they can be spawned just off screen).
+    pub offscreen_despawn: Components<bool>,
+}
+
+/// Stands in for an entity when it collides with a `GameObject`, since
+/// `GameMessage::Hit` needs a `GameObject` to point at.
+struct EntityProxy {
+    bounds: Bounds,
+    kind: ObjectKind,
+}
+
+impl GameObject<Keys, Texture> for EntityProxy {
+    fn update(
+        &mut self,
+        _: &mut Context<Keys>,
+        _: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        vec![]
+    }
+
+    fn sprites(&self, _: GameTime) -> Vec<(VisibleComponent<Texture>, Dest)> {
+        vec![]
+    }
+
+    fn bounds(&self) -> Option<Bounds> { Some(self.bounds) }
+
+    fn kind(&self) -> ObjectKind { self.kind }
+}
+
+/// Projectiles fly straight through each other.
+fn passes_through(a: ObjectKind, b: ObjectKind) -> bool {
+    a == ObjectKind::Projectile && b == ObjectKind::Projectile
+}
+
+fn translate_bounds(bounds: Bounds, by: Vec2) -> Bounds {
+    match bounds {
+        Bounds::Rectangle(mut r) => { r.translate(by); r.into() },
+        Bounds::Circle(mut c) => { c.translate(by); c.into() },
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn spawn(&mut self, spec: EntitySpec) -> Entity {
+        let entity = self.entities.allocate();
+
+        self.live.push(entity);
+        self.positions.insert(entity, spec.position);
+
+        if let Some(v) = spec.velocity {
+            self.velocities.insert(entity, v);
+        }
+        if let Some(a) = spec.acceleration {
+            self.accelerations.insert(entity, a);
+        }
+        if let Some(t) = spec.launch_at {
+            self.launches.insert(entity, t);
+        }
+        if let Some(c) = spec.collider {
+            self.colliders.insert(entity, c);
+        }
+        if let Some(a) = spec.appearance {
+            self.appearances.insert(entity, a);
+        }
+        if let Some(h) = spec.health {
+            self.health.insert(entity, h);
+        }
+        if let Some(size) = spec.explosion {
+            self.explosions.insert(entity, size);
+        }
+        if let Some(l) = spec.lifetime {
+            self.lifetimes.insert(entity, l);
+        }
+        if spec.despawn_offscreen {
+            self.offscreen_despawn.insert(entity, false);
+        }
+
+        entity
+    }
+
+    pub fn despawn(&mut self, entity: Entity) {
+        if !self.entities.release(entity) { return; }
+
+        self.live.retain(|&e| e != entity);
+
+        self.positions.remove(entity);
+        self.velocities.remove(entity);
+        self.accelerations.remove(entity);
+        self.launches.remove(entity);
+        self.colliders.remove(entity);
+        self.appearances.remove(entity);
+        self.health.remove(entity);
+        self.explosions.remove(entity);
+        self.lifetimes.remove(entity);
+        self.offscreen_despawn.remove(entity);
+    }
+
+    /// Lets the world react to a broadcast, like objects do in
+    /// `receive_message`.
+    pub fn receive(&mut self, event: &GameEvent) {
+        if let GameEvent::BombDetonated = *event {
+            // Bombs clear the screen of enemy bullets
+            let cleared = self.live.iter().cloned().filter(|&e|
+                self.colliders.get(e).map_or(false, |c|
+                    c.team == DamageFilter::Enemy &&
+                    c.kind == ObjectKind::Projectile
+                )
+            ).collect::<Vec<_>>();
+
+            for e in cleared {
+                self.despawn(e);
+            }
+        }
+    }
+
+    /// Where the entity's collider is in the world, if it has both.
+    pub fn world_bounds(&self, entity: Entity) -> Option<Bounds> {
+        match (self.positions.get(entity), self.colliders.get(entity)) {
+            (Some(&p), Some(c)) => Some(translate_bounds(c.shape, p)),
+            _ => None,
+        }
+    }
+
+    fn visual_bounds(&self, entity: Entity) -> Option<BoundingRect> {
+        match (self.positions.get(entity), self.appearances.get(entity)) {
+            (Some(&p), Some(a)) => Some(
+                BoundingRect {
+                    width: a.size.x,
+                    height: a.size.y,
+                    .. Default::default()
+                }.at(p)
+            ),
+            _ => None,
+        }
+    }
+
+    /// Moves everything with a velocity, once it's been launched.
+    pub fn integrate(&mut self, time: GameTime) {
+        use ::gameobjects::MinMax;
+
+        let dt = time.elapsed.exact_seconds();
+
+        for &e in &self.live {
+            if self.launches.get(e).map_or(false, |&at| time.total < at) {
+                continue;
+            }
+
+            if let (Some(v), Some(a)) = (
+                self.velocities.get_mut(e),
+                self.accelerations.get(e)
+            ) {
+                let speed = (v.length() + a.rate * dt).limit(0.0, a.max_speed);
+
+                *v = a.direction * speed;
+            }
+
+            if let (Some(p), Some(&v)) = (
+                self.positions.get_mut(e),
+                self.velocities.get(e)
+            ) {
+                *p += v * dt;
+            }
+        }
+    }
+
+    fn on_screen(&self, entity: Entity, play_area: BoundingRect) -> bool {
+        self.visual_bounds(entity).map(Bounds::from)
+            .or(self.world_bounds(entity))
+            .map_or(false, |b| b.intersects(&play_area.into()))
+    }
+
+    /// Despawns entities that have outlived their `Lifetime`, or that are
+    /// marked for it and have left `play_area` after having been inside it.
+    pub fn expire(&mut self, time: GameTime, play_area: BoundingRect) {
+        for &e in &self.live {
+            let visible = self.on_screen(e, play_area);
+
+            if let Some(entered) = self.offscreen_despawn.get_mut(e) {
+                *entered = *entered || visible;
+            }
+        }
+
+        let expired = self.live.iter().cloned().filter(|&e|
+            self.lifetimes.get(e)
+                .map_or(false, |l| l.expires_at <= time.total) ||
+            self.offscreen_despawn.get(e)
+                .map_or(false, |&entered| entered) &&
+                !self.on_screen(e, play_area)
+        ).collect::<Vec<_>>();
+
+        for e in expired {
+            self.despawn(e);
+        }
+    }
+
+    fn damage(&mut self, entity: Entity, info: &DamageInfo) {
+        let (team, despawn_on_hit) = match self.colliders.get(entity) {
+            Some(c) => (c.team, c.despawn_on_hit),
+            None => return,
+        };
+
+        if team == info.filter { return; }
+
+        if despawn_on_hit && !self.killed.contains(&entity) {
+            self.killed.push(entity);
+        }
+
+        if let Some(h) = self.health.get_mut(entity) {
+            h.hp = h.hp.saturating_sub(info.damage);
+        }
+    }
+
+    fn kind_of(&self, entity: Entity) -> ObjectKind {
+        self.colliders.get(entity).map_or(ObjectKind::Other, |c| c.kind)
+    }
+
+    fn hit_info(&self, entity: Entity) -> Option<DamageInfo> {
+        self.colliders.get(entity).map(|c|
+            DamageInfo {
+                filter: c.team,
+                damage: c.damage,
+            }
+        )
+    }
+
+    /// Collides entities with each other and with `objects`. Messages that
+    /// the objects send back in response are appended to `messages`, which
+    /// must line up with `objects`.
+    pub fn collide(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime,
+        objects: &mut [(Handle, SimpleObject)],
+        messages: &mut [Vec<GameAction<Keys, Texture>>]
+    ) {
+        let colliding = self.live.iter().cloned()
+            .filter_map(|e| self.world_bounds(e).map(|b| (e, b)))
+            .collect::<Vec<_>>();
+
+        for (i, &(a, a_bounds)) in colliding.iter().enumerate() {
+            let a_kind = self.kind_of(a);
+
+            for &(b, b_bounds) in &colliding[i + 1..] {
+                if
+                    passes_through(a_kind, self.kind_of(b)) ||
+                    !a_bounds.intersects(&b_bounds)
+                {
+                    continue;
+                }
+
+                let (a_info, b_info) = (self.hit_info(a), self.hit_info(b));
+
+                if let Some(info) = b_info { self.damage(a, &info); }
+                if let Some(info) = a_info { self.damage(b, &info); }
+            }
+
+            for (&mut (_, ref mut obj), msgs) in
+                objects.iter_mut().zip(messages.iter_mut())
+            {
+                // Something that's already been used up on one object can't
+                // hit another in the same tick
+                if self.killed.contains(&a) { break; }
+
+                if
+                    passes_through(a_kind, obj.kind()) ||
+                    !obj.bounds().map_or(false, |b| b.intersects(&a_bounds))
+                {
+                    continue;
+                }
+
+                // Entities have no masks of their own, but objects with one
+                // are only hit where they're solid
+                let solid = obj.collision_mask(time).map_or(
+                    true,
+                    |(mask, dest)| bounds_dest(a_bounds)
+                        .map_or(true, |rect| mask.overlaps_rect(dest, rect))
+                );
+
+                if !solid { continue; }
+
+                if let Some(GameMessage::Hit { info, .. }) = obj.on_hit() {
+                    self.damage(a, &info);
+                }
+
+                if let Some(info) = self.hit_info(a) {
+                    let proxy = EntityProxy {
+                        bounds: a_bounds,
+                        kind: a_kind,
+                    };
+
+                    msgs.extend(
+                        obj.receive_message(
+                            context,
+                            time,
+                            GameMessage::Hit {
+                                other: &proxy,
+                                info: info,
+                            }
+                        )
+                    );
+                }
+            }
+        }
+    }
+
+    /// Despawns entities that have run out of health or been used up by
+    /// hitting something, returning the objects their deaths leave behind.
+    pub fn reap(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<SimpleObject> {
+        let mut dead = ::std::mem::replace(&mut self.killed, vec![]);
+        let out_of_health = self.live.iter().cloned().filter(|&e|
+            self.health.get(e).map_or(false, |h| h.hp == 0) &&
+                !dead.contains(&e)
+        ).collect::<Vec<_>>();
+
+        dead.extend(out_of_health);
+
+        let mut remains = vec![];
+
+        for e in dead {
+            if let (Some(&p), Some(&size)) = (
+                self.positions.get(e),
+                self.explosions.get(e)
+            ) {
+                remains.push(
+                    box Explosion::with_bounds(
+                        &mut context.renderer,
+                        time.total,
+                        BoundingRect {
+                            width: size.x,
+                            height: size.y,
+                            .. Default::default()
+                        }.at(p)
+                    ) as SimpleObject
+                );
+            }
+
+            self.despawn(e);
+        }
+
+        remains
+    }
+
+    /// Runs every system for one tick.
+    pub fn update(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime,
+        play_area: BoundingRect,
+        objects: &mut [(Handle, SimpleObject)],
+        messages: &mut [Vec<GameAction<Keys, Texture>>]
+    ) -> Vec<SimpleObject> {
+        self.integrate(time);
+        self.expire(time, play_area);
+        self.collide(context, time, objects, messages);
+        self.reap(context, time)
+    }
+
+    pub fn sprites(&self) -> Vec<(VisibleComponent<Texture>, Dest)> {
+        self.live.iter().filter_map(|&e|
+            match (self.appearances.get(e), self.visual_bounds(e)) {
+                (Some(a), Some(bounds)) => bounds.try_into().ok().map(|dest|
+                    (VisibleRect(a.color).into(), dest)
+                ),
+                _ => None,
+            }
+        ).collect()
+    }
+}
@@ -0,0 +1,108 @@
+use ::gameobjects::*;
+use ::graphics::sprites::{VisibleComponent, VisibleRect};
+use ::graphics::backend::Texture;
+
+use std::cmp;
+use sdl2::pixels::Color;
+
+/// How far the screen moves at full trauma, in pixels.
+const MAX_SHAKE: f64 = 14.0;
+/// How much trauma wears off each second.
+const TRAUMA_DECAY: f64 = 1.6;
+/// The longest the game can be frozen for at once, so that hit-stops from
+/// lots of things at once don't add up to a pause.
+const MAX_HIT_STOP: u32 = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScreenEffect {
+    /// Adds trauma, from 0 to 1. The screen shakes with the square of the
+    /// trauma, so small knocks barely move it while big ones add up fast.
+    Shake(f64),
+    /// Freezes game time for some milliseconds.
+    HitStop(u32),
+    /// Covers the screen in a colour that fades out over some milliseconds.
+    /// The colour's alpha is how strong the flash starts out.
+    Flash(Color, u32),
+}
+
+/// Screen shake, hit-stop and flashes for a view. These run on real time
+/// rather than game time, since game time stops during a hit-stop.
+#[derive(Default)]
+pub struct ScreenEffects {
+    clock: u32,
+    trauma: f64,
+    frozen_until: u32,
+    flash: Option<(Color, u32, u32)>,
+}
+
+impl ScreenEffects {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn apply(&mut self, effect: ScreenEffect) {
+        use ::gameobjects::MinMax;
+
+        match effect {
+            ScreenEffect::Shake(trauma) =>
+                self.trauma = (self.trauma + trauma).limit(0.0, 1.0),
+            ScreenEffect::HitStop(duration) => self.frozen_until = cmp::max(
+                self.frozen_until,
+                self.clock + cmp::min(duration, MAX_HIT_STOP)
+            ),
+            ScreenEffect::Flash(color, duration) =>
+                self.flash = Some((color, self.clock, duration)),
+        }
+    }
+
+    /// Moves the effects on by `elapsed` milliseconds of real time,
+    /// returning how much of that the game itself should move on by.
+    pub fn update(&mut self, elapsed: u32) -> u32 {
+        use ::time::TimeExtensions;
+
+        let frozen_for = self.frozen_until.saturating_sub(self.clock);
+
+        self.clock += elapsed;
+        self.trauma = (self.trauma - TRAUMA_DECAY * elapsed.exact_seconds())
+            .max(0.0);
+
+        if let Some((_, started_at, duration)) = self.flash {
+            if self.clock >= started_at + duration {
+                self.flash = None;
+            }
+        }
+
+        elapsed.saturating_sub(frozen_for)
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.clock < self.frozen_until
+    }
+
+    /// How far to move everything drawn this frame.
+    pub fn shake_offset(&self) -> (i32, i32) {
+        use rand::random;
+
+        let strength = self.trauma * self.trauma * MAX_SHAKE;
+        let wobble = || (random::<f64>() * 2.0 - 1.0) * strength;
+
+        (wobble() as i32, wobble() as i32)
+    }
+
+    /// The flash covering `screen`, if there is one.
+    pub fn flash(&self, screen: Dest)
+        -> Option<(VisibleComponent<Texture>, Dest)>
+    {
+        self.flash.map(|(color, started_at, duration)| {
+            let age = (self.clock - started_at) as f64;
+            let left = 1.0 - age / cmp::max(duration, 1) as f64;
+            let (r, g, b, a) = color.rgba();
+
+            (
+                VisibleRect(Color::RGBA(r, g, b, (a as f64 * left) as u8))
+                    .into(),
+                screen,
+            )
+        })
+    }
+}
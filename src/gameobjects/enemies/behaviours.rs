@@ -0,0 +1,174 @@
+use ::gameobjects::*;
+use ::gameobjects::ecs::EntitySpec;
+use ::events::*;
+use ::view::*;
+use ::time::*;
+
+use super::Behaviour;
+use super::patterns::PatternSequence;
+
+/// Flies at a constant velocity and never fires.
+pub struct StraightFlier {
+    pub velocity: Vec2,
+}
+
+impl StraightFlier {
+    pub fn new(velocity: Vec2) -> Self {
+        StraightFlier {
+            velocity: velocity,
+        }
+    }
+}
+
+impl Behaviour for StraightFlier {
+    fn act(
+        &mut self,
+        bounds: &mut BoundingRect,
+        _: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<EntitySpec> {
+        bounds.translate(self.velocity * time.elapsed.exact_seconds());
+
+        vec![]
+    }
+}
+
+/// Flies left while weaving up and down around the height it spawned at.
+pub struct SineWeaver {
+    pub origin: Vec2,
+    pub speed: f64,
+    pub amplitude: f64,
+    /// In radians per second.
+    pub frequency: f64,
+    pub born_at: u32,
+}
+
+impl SineWeaver {
+    pub fn new(
+        origin: Vec2,
+        speed: f64,
+        amplitude: f64,
+        frequency: f64,
+        now: u32
+    ) -> Self {
+        SineWeaver {
+            origin: origin,
+            speed: speed,
+            amplitude: amplitude,
+            frequency: frequency,
+            born_at: now,
+        }
+    }
+}
+
+impl Behaviour for SineWeaver {
+    fn act(
+        &mut self,
+        bounds: &mut BoundingRect,
+        _: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<EntitySpec> {
+        let t = (time.total - self.born_at).exact_seconds();
+
+        *bounds = bounds.at(
+            self.origin + Vec2::new(
+                -self.speed * t,
+                self.amplitude * (self.frequency * t).sin()
+            )
+        );
+
+        vec![]
+    }
+}
+
+/// Steers towards the player, turning at a limited rate so that it can be
+/// dodged.
+pub struct Chaser {
+    pub velocity: Vec2,
+    pub speed: f64,
+    /// How much of the way towards facing the player the chaser turns each
+    /// second.
+    pub turn_rate: f64,
+}
+
+impl Chaser {
+    pub fn new(velocity: Vec2, speed: f64, turn_rate: f64) -> Self {
+        Chaser {
+            velocity: velocity,
+            speed: speed,
+            turn_rate: turn_rate,
+        }
+    }
+}
+
+impl Behaviour for Chaser {
+    fn act(
+        &mut self,
+        bounds: &mut BoundingRect,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<EntitySpec> {
+        use ::gameobjects::MinMax;
+
+        let dt = time.elapsed.exact_seconds();
+
+        let target = context.objects.player()
+            .and_then(|p| p.bounds)
+            .map(|b| b.center());
+
+        if let Some(target) = target {
+            let desired = (target - bounds.center()).normalize() * self.speed;
+
+            self.velocity = self.velocity.lerp(
+                desired,
+                (self.turn_rate * dt).min(1.0)
+            );
+        }
+
+        bounds.translate(self.velocity * dt);
+
+        vec![]
+    }
+}
+
+/// Flies in until it reaches `stop_x`, then sits still and runs its firing
+/// pattern at the player.
+pub struct Turret {
+    pub stop_x: f64,
+    pub speed: f64,
+    pub pattern: PatternSequence,
+}
+
+impl Turret {
+    pub fn new(stop_x: f64, speed: f64, pattern: PatternSequence) -> Self {
+        Turret {
+            stop_x: stop_x,
+            speed: speed,
+            pattern: pattern,
+        }
+    }
+}
+
+impl Behaviour for Turret {
+    fn act(
+        &mut self,
+        bounds: &mut BoundingRect,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<EntitySpec> {
+        use ::gameobjects::MinMax;
+
+        if bounds.x > self.stop_x {
+            bounds.x = (bounds.x - self.speed * time.elapsed.exact_seconds())
+                .max(self.stop_x);
+
+            return vec![];
+        }
+
+        let target = context.objects.player()
+            .and_then(|p| p.bounds)
+            .map(|b| b.center());
+
+        self.pattern.update(bounds.center(), target, time)
+    }
+}
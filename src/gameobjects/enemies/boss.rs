@@ -0,0 +1,650 @@
+use ::gameobjects::*;
+use ::gameobjects::object_table::ObjectKind;
+use ::gameobjects::handle::Handle;
+use ::gameobjects::broadcast::{Broadcast, GameEvent, Topic};
+use ::gameobjects::pickups::BOSS_DROPS;
+use ::gameobjects::effects::ScreenEffect;
+use ::gameobjects::player::{
+    GameObject,
+    GameAction,
+    GameMessage,
+    DamageInfo,
+    DamageFilter,
+    Explosion,
+    SimpleObject,
+};
+use ::graphics::backend::{Backend, Texture};
+use ::graphics::sprites::{
+    build_spritesheet,
+    AnimatedSprite,
+    LoadSprite,
+    Sprite,
+    VisibleComponent,
+};
+use ::graphics::alpha_mask::{AlphaMask, shared_spritesheet_masks};
+use ::events::*;
+use ::view::*;
+use ::time::*;
+
+use std::cmp;
+use std::convert::TryInto;
+use std::io::{self, Write};
+use std::rc::Rc;
+use sdl2::pixels::Color;
+
+use super::patterns::*;
+
+const HULL_PATH: &'static str = "assets/boss.png";
+const PARTS_PATH: &'static str = "assets/boss_parts.png";
+const HULL_SIZE: [u32; 2] = [256, 160];
+const PART_SIZE: u32 = 48;
+const FRAMES_PER_PART: usize = 4;
+
+const INTRO_LENGTH: u32 = 3000;
+const DEATH_LENGTH: u32 = 2500;
+const DEATH_EXPLOSIONS: u32 = 10;
+
+/// The fractions of its total health below which the boss moves on to its
+/// next phase.
+const PHASE_THRESHOLDS: [f64; 2] = [0.66, 0.33];
+
+/// Where the boss is at any time. Every part of a boss shares the same
+/// motion, so they stay together without having to tell each other where
+/// they are.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BossMotion {
+    pub spawned_at: u32,
+    pub entry: Vec2,
+    pub rest: Vec2,
+}
+
+impl BossMotion {
+    /// Whether the boss is still flying in. Bosses can't be hurt and don't
+    /// fire during their intro.
+    pub fn in_intro(&self, now: u32) -> bool {
+        now - self.spawned_at < INTRO_LENGTH
+    }
+
+    /// The top left of the boss's hull at `now`.
+    pub fn position_at(&self, now: u32) -> Vec2 {
+        let t = now - self.spawned_at;
+
+        if t < INTRO_LENGTH {
+            let progress = t.exact_seconds() / INTRO_LENGTH.exact_seconds();
+
+            // Eased out, so that the boss slows to a stop
+            self.entry.lerp(self.rest, 1.0 - (1.0 - progress).powi(2))
+        } else {
+            let t = (t - INTRO_LENGTH).exact_seconds();
+
+            self.rest + Vec2::new(0.0, 60.0 * (t * 0.8).sin())
+        }
+    }
+}
+
+/// The body of a boss. The hull itself is armoured, and the boss is beaten
+/// by destroying its core. It spawns its parts when it first updates, and
+/// keeps track of their combined health to decide which phase it's in.
+pub struct Boss {
+    handle: Option<Handle>,
+    motion: BossMotion,
+    hull: Sprite<Texture>,
+    masks: Rc<Vec<AlphaMask>>,
+    bounds: BoundingRect,
+    hp: u32,
+    max_hp: u32,
+    phase: usize,
+    parts_spawned: bool,
+    dying_since: Option<u32>,
+    explosions_spawned: u32,
+}
+
+impl Boss {
+    /// A boss that flies in from the right of `play_area` and comes to rest
+    /// centered on `center`.
+    pub fn new(
+        renderer: &mut Backend,
+        now: u32,
+        center: Vec2,
+        play_area: BoundingRect
+    ) -> Self {
+        let [w, h] = HULL_SIZE;
+
+        let bounds = BoundingRect {
+            width: w as _,
+            height: h as _,
+            .. Default::default()
+        }.centered_at(center);
+
+        let max_hp: u32 = ALL_PARTS.iter().map(|&(role, _)| role.hp()).sum();
+
+        // Without a mask the boss still collides, just with its bounds
+        let masks = shared_spritesheet_masks(HULL_PATH, w, h)
+            .unwrap_or_else(|e| {
+                let _ = writeln!(io::stderr(), "{}: {}", HULL_PATH, e);
+
+                Rc::new(vec![])
+            });
+
+        Boss {
+            handle: None,
+            motion: BossMotion {
+                spawned_at: now,
+                entry: Vec2::new(play_area.right(), bounds.y),
+                rest: bounds.position(),
+            },
+            hull: renderer.load_sprite(HULL_PATH).unwrap(),
+            masks: masks,
+            bounds: bounds.with_position(play_area.right(), bounds.y),
+            hp: max_hp,
+            max_hp: max_hp,
+            phase: 0,
+            parts_spawned: false,
+            dying_since: None,
+            explosions_spawned: 0,
+        }
+    }
+
+    fn death_explosions(
+        &mut self,
+        renderer: &mut Backend,
+        now: u32,
+        since: u32
+    ) -> Vec<SimpleObject> {
+        use rand::random;
+
+        let due = cmp::min(
+            DEATH_EXPLOSIONS,
+            (now - since) * DEATH_EXPLOSIONS / DEATH_LENGTH + 1
+        );
+
+        let mut out = vec![];
+
+        while self.explosions_spawned < due {
+            let position = self.bounds.position() + Vec2::new(
+                random::<f64>() * self.bounds.width - 48.0,
+                random::<f64>() * self.bounds.height - 48.0
+            );
+
+            out.push(
+                box Explosion::new(renderer, now, position) as SimpleObject
+            );
+
+            self.explosions_spawned += 1;
+        }
+
+        out
+    }
+}
+
+impl GameObject<Keys, Texture> for Boss {
+    fn update(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let now = time.total;
+        let mut out = vec![];
+
+        self.bounds = self.bounds.at(self.motion.position_at(now));
+
+        if !self.parts_spawned {
+            self.parts_spawned = true;
+
+            if let Some(handle) = self.handle {
+                out.push(
+                    GameAction::AddObjects(
+                        ALL_PARTS.iter().map(|&(role, offset)|
+                            box BossPart::new(
+                                context.renderer,
+                                now,
+                                role,
+                                handle,
+                                self.motion,
+                                Vec2::from(offset)
+                            ) as SimpleObject
+                        ).collect()
+                    )
+                );
+            }
+
+            out.push(
+                GameAction::Broadcast(
+                    vec![
+                        Broadcast::to_subscribers(
+                            GameEvent::BossPhaseChanged { phase: 0 }
+                        ),
+                    ]
+                )
+            );
+        }
+
+        if let Some(since) = self.dying_since {
+            let mut explosions =
+                self.death_explosions(context.renderer, now, since);
+
+            if now - since >= DEATH_LENGTH {
+                explosions.push(
+                    box Explosion::with_bounds(
+                        context.renderer,
+                        now,
+                        self.bounds
+                    )
+                );
+                explosions.extend(
+                    BOSS_DROPS.spawn(
+                        context.renderer,
+                        now,
+                        self.bounds.center()
+                    )
+                );
+
+                out.push(
+                    GameAction::ScreenEffects(
+                        vec![
+                            ScreenEffect::Shake(1.0),
+                            ScreenEffect::HitStop(200),
+                            ScreenEffect::Flash(
+                                Color::RGBA(255, 255, 255, 230),
+                                800
+                            ),
+                        ]
+                    )
+                );
+                out.push(GameAction::Delete);
+            } else if !explosions.is_empty() {
+                out.push(
+                    GameAction::ScreenEffects(vec![ScreenEffect::Shake(0.3)])
+                );
+            }
+
+            if !explosions.is_empty() {
+                out.push(GameAction::AddObjects(explosions));
+            }
+        }
+
+        out
+    }
+
+    fn sprites(&self, _: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        vec![
+            (self.hull.clone().into(), self.bounds.try_into().unwrap()),
+        ]
+    }
+
+    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
+
+    fn collision_mask(&self, _: GameTime) -> Option<(&AlphaMask, Dest)> {
+        let dest = self.bounds.try_into().unwrap();
+
+        self.masks.first().map(|mask| (mask, dest))
+    }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Enemy }
+
+    fn boss_health(&self) -> Option<f64> {
+        if self.dying_since.is_none() && self.hp > 0 {
+            Some(self.hp as f64 / self.max_hp as f64)
+        } else {
+            None
+        }
+    }
+
+    fn on_spawn(&mut self, handle: Handle) {
+        self.handle = Some(handle);
+    }
+
+    fn receive_message<'a>(
+        &'a mut self,
+        _: &mut Context<Keys>,
+        time: GameTime,
+        msg: GameMessage<'a, Keys, Texture>
+    ) -> Vec<GameAction<Keys, Texture>> {
+        match msg {
+            GameMessage::Event {
+                event: GameEvent::BossDamaged { damage },
+                ..
+            } => {
+                self.hp = self.hp.saturating_sub(damage);
+
+                let (hp, max_hp) = (self.hp as f64, self.max_hp as f64);
+                let phase = PHASE_THRESHOLDS.iter()
+                    .filter(|&&threshold| hp < threshold * max_hp)
+                    .count();
+
+                if phase > self.phase {
+                    self.phase = phase;
+
+                    vec![
+                        GameAction::Broadcast(
+                            vec![
+                                Broadcast::to_subscribers(
+                                    GameEvent::BossPhaseChanged {
+                                        phase: phase,
+                                    }
+                                ),
+                            ]
+                        ),
+                    ]
+                } else {
+                    vec![]
+                }
+            },
+            GameMessage::Event { event: GameEvent::BossDefeated, .. }
+                if self.dying_since.is_none() =>
+            {
+                self.dying_since = Some(time.total);
+
+                vec![
+                    GameAction::Broadcast(
+                        vec![Broadcast::to_subscribers(GameEvent::BossDefeated)]
+                    ),
+                ]
+            },
+            _ => vec![],
+        }
+    }
+
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        if self.dying_since.is_some() { return None; }
+
+        Some(
+            GameMessage::Hit {
+                other: self as _,
+                info: DamageInfo {
+                    damage: 40,
+                    filter: DamageFilter::Enemy,
+                },
+            }
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PartRole {
+    Core,
+    Turret,
+}
+
+/// Each part of the boss and where it sits relative to the top left of the
+/// hull.
+static ALL_PARTS: [(PartRole, [f64; 2]); 3] = [
+    (PartRole::Core, [72.0, 56.0]),
+    (PartRole::Turret, [131.0, -8.0]),
+    (PartRole::Turret, [131.0, 120.0]),
+];
+
+impl PartRole {
+    /// Which row of the parts spritesheet this role's animation is on.
+    fn row(&self) -> usize {
+        match *self {
+            PartRole::Turret => 0,
+            PartRole::Core => 1,
+        }
+    }
+
+    fn hp(&self) -> u32 {
+        match *self {
+            PartRole::Core => 600,
+            PartRole::Turret => 200,
+        }
+    }
+
+    /// What this part fires during `phase` of the fight.
+    fn pattern(&self, phase: usize) -> PatternSequence {
+        let fast = BulletSpec {
+            speed: 300.0,
+            .. Default::default()
+        };
+        let blooming = BulletSpec {
+            speed: 40.0,
+            acceleration: 150.0,
+            max_speed: 260.0,
+            delay: 400,
+            .. Default::default()
+        };
+
+        let steps = match (*self, phase) {
+            (PartRole::Turret, 0) => vec![
+                Step::Fire(
+                    Pattern::new(Shape::AimedFan { count: 3, spread: 0.3 })
+                        .repeat(2, 250)
+                ),
+                Step::Wait(1200),
+            ],
+            (PartRole::Turret, 1) => vec![
+                Step::Fire(
+                    Pattern::new(
+                        Shape::Spiral { count: 4, angle: 0.0, spin: 0.35 }
+                    ).repeat(12, 120)
+                ),
+                Step::Wait(800),
+            ],
+            (PartRole::Turret, _) => vec![
+                Step::Fire(
+                    Pattern::new(Shape::AimedFan { count: 5, spread: 0.8 })
+                        .with_bullet(fast)
+                        .repeat(3, 200)
+                ),
+                Step::Wait(500),
+            ],
+            (PartRole::Core, 0) => vec![],
+            (PartRole::Core, 1) => vec![
+                Step::Fire(
+                    Pattern::new(Shape::Radial { count: 16, angle: 0.0 })
+                        .with_bullet(blooming)
+                ),
+                Step::Wait(2000),
+            ],
+            (PartRole::Core, _) => vec![
+                Step::Fire(
+                    Pattern::new(
+                        Shape::Spiral { count: 6, angle: 0.0, spin: 0.2 }
+                    ).repeat(20, 100)
+                ),
+                Step::Fire(
+                    Pattern::new(Shape::Radial { count: 24, angle: 0.0 })
+                        .with_bullet(blooming)
+                ),
+                Step::Wait(600),
+            ],
+        };
+
+        PatternSequence::new(steps, true)
+    }
+}
+
+/// A piece of a boss that can be shot at. Damage to a part is passed on to
+/// the boss, and destroying the core beats the boss.
+pub struct BossPart {
+    pub role: PartRole,
+    pub boss: Handle,
+    pub motion: BossMotion,
+    pub offset: Vec2,
+    pub sprite: AnimatedSprite<u32, Texture>,
+    pub bounds: BoundingRect,
+    pub hp: u32,
+    pub pattern: PatternSequence,
+}
+
+impl BossPart {
+    pub fn new(
+        renderer: &mut Backend,
+        now: u32,
+        role: PartRole,
+        boss: Handle,
+        motion: BossMotion,
+        offset: Vec2
+    ) -> Self {
+        BossPart {
+            role: role,
+            boss: boss,
+            motion: motion,
+            offset: offset,
+            sprite: AnimatedSprite::from_spritesheet(
+                now,
+                6.0,
+                build_spritesheet(
+                    renderer.load_image(PARTS_PATH.as_ref()).unwrap(),
+                    PART_SIZE,
+                    PART_SIZE
+                ).into_iter()
+                    .skip(role.row() * FRAMES_PER_PART)
+                    .take(FRAMES_PER_PART)
+                    .collect()
+            ),
+            bounds: BoundingRect {
+                width: PART_SIZE as _,
+                height: PART_SIZE as _,
+                .. Default::default()
+            }.at(motion.position_at(now) + offset),
+            hp: role.hp(),
+            pattern: role.pattern(0),
+        }
+    }
+
+    fn explode(&self, renderer: &mut Backend, now: u32)
+        -> GameAction<Keys, Texture>
+    {
+        GameAction::AddObjects(
+            vec![box Explosion::with_bounds(renderer, now, self.bounds)]
+        )
+    }
+}
+
+impl GameObject<Keys, Texture> for BossPart {
+    fn update(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        self.bounds = self.bounds.at(
+            self.motion.position_at(time.total) + self.offset
+        );
+
+        if self.motion.in_intro(time.total) { return vec![]; }
+
+        let target = context.objects.player()
+            .and_then(|p| p.bounds)
+            .map(|b| b.center());
+
+        let bullets = self.pattern.update(self.bounds.center(), target, time);
+
+        if bullets.is_empty() {
+            vec![]
+        } else {
+            vec![GameAction::SpawnEntities(bullets)]
+        }
+    }
+
+    fn sprites(&self, time: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        vec![
+            (
+                self.sprite.frame(time.total).clone().into(),
+                self.bounds.try_into().unwrap(),
+            )
+        ]
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let Vec2 { x, y } = self.bounds.center();
+
+        Some(
+            Circle {
+                x: x,
+                y: y,
+                radius: self.bounds.width * 0.45,
+            }.into()
+        )
+    }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Enemy }
+
+    fn is_subscribed(&self, topic: Topic) -> bool {
+        topic == Topic::Boss
+    }
+
+    fn receive_message<'a>(
+        &'a mut self,
+        ctx: &mut Context<Keys>,
+        time: GameTime,
+        msg: GameMessage<'a, Keys, Texture>
+    ) -> Vec<GameAction<Keys, Texture>> {
+        // A destroyed part has already told the boss, and is only waiting to
+        // be deleted
+        if self.hp == 0 { return vec![]; }
+
+        match msg {
+            GameMessage::Hit {
+                info: DamageInfo { filter: DamageFilter::Player, damage },
+                ..
+            } => {
+                if damage == 0 || self.motion.in_intro(time.total) {
+                    return vec![];
+                }
+
+                let damage = cmp::min(damage, self.hp);
+
+                self.hp -= damage;
+
+                let mut broadcasts = vec![
+                    Broadcast::to_object(
+                        self.boss,
+                        GameEvent::BossDamaged { damage: damage }
+                    ),
+                ];
+
+                if self.hp > 0 {
+                    return vec![GameAction::Broadcast(broadcasts)];
+                }
+
+                if self.role == PartRole::Core {
+                    broadcasts.push(
+                        Broadcast::to_object(
+                            self.boss,
+                            GameEvent::BossDefeated
+                        )
+                    );
+                }
+
+                vec![
+                    GameAction::Broadcast(broadcasts),
+                    self.explode(&mut ctx.renderer, time.total),
+                    GameAction::Delete,
+                ]
+            },
+            GameMessage::Event {
+                sender,
+                event: GameEvent::BossPhaseChanged { phase },
+            } if sender == Some(self.boss) => {
+                self.pattern = self.role.pattern(phase);
+
+                vec![]
+            },
+            GameMessage::Event {
+                sender,
+                event: GameEvent::BossDefeated,
+            } if sender == Some(self.boss) => vec![
+                self.explode(&mut ctx.renderer, time.total),
+                GameAction::Delete,
+            ],
+            _ => vec![],
+        }
+    }
+
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        Some(
+            GameMessage::Hit {
+                other: self as _,
+                info: DamageInfo {
+                    damage: 20,
+                    filter: DamageFilter::Enemy,
+                },
+            }
+        )
+    }
+}
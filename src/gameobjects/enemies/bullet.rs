@@ -0,0 +1,51 @@
+use ::gameobjects::*;
+use ::gameobjects::object_table::ObjectKind;
+use ::gameobjects::ecs::{Acceleration, Collider, EntitySpec};
+use ::gameobjects::player::DamageFilter;
+
+use sdl2::pixels::Color;
+
+use super::patterns::BulletSpec;
+
+const SIZE: f64 = 6.0;
+
+/// An enemy bullet centred on `center`, fired at `now` in the direction
+/// `angle`. Bullet patterns fire lots of these, so they're entities that the
+/// view's `World` moves and collides, rather than objects of their own. They
+/// go away when they touch the player, leave the play area or are caught in
+/// a bomb.
+pub fn enemy_bullet(
+    center: Vec2,
+    angle: f64,
+    spec: &BulletSpec,
+    now: u32
+) -> EntitySpec {
+    let direction = Vec2::from_angle(angle);
+    let size = Vec2::new(SIZE, SIZE);
+
+    EntitySpec::at(center - size / 2.0)
+        .velocity(direction * spec.speed)
+        .acceleration(
+            Acceleration {
+                direction: direction,
+                rate: spec.acceleration,
+                max_speed: spec.max_speed,
+            }
+        )
+        .launch_at(now + spec.delay)
+        .collider(
+            Collider {
+                shape: BoundingRect {
+                    width: size.x,
+                    height: size.y,
+                    .. Default::default()
+                }.into(),
+                team: DamageFilter::Enemy,
+                damage: spec.damage,
+                kind: ObjectKind::Projectile,
+                despawn_on_hit: true,
+            }
+        )
+        .appearance(Color::RGB(255, 120, 40), size)
+        .despawn_offscreen()
+}
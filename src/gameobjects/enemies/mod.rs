@@ -0,0 +1,396 @@
+use ::gameobjects::*;
+use ::gameobjects::object_table::ObjectKind;
+use ::gameobjects::pickups::ENEMY_DROPS;
+use ::gameobjects::particles::{Emitter, EXPLOSION_SPARKS};
+use ::gameobjects::effects::ScreenEffect;
+use ::gameobjects::ecs::EntitySpec;
+use ::gameobjects::player::{
+    GameObject,
+    GameAction,
+    GameMessage,
+    DamageInfo,
+    DamageFilter,
+    Explosion,
+    SimpleObject,
+};
+use ::graphics::backend::{Backend, Texture};
+use ::graphics::sprites::{
+    build_spritesheet,
+    AnimatedSprite,
+    VisibleComponent,
+    Sprite,
+};
+use ::events::*;
+use ::view::*;
+
+use std::convert::TryInto;
+use sdl2::pixels::Color;
+
+pub mod behaviours;
+pub mod bullet;
+pub mod patterns;
+pub mod boss;
+
+use self::behaviours::*;
+use self::patterns::*;
+
+const ENEMIES_PATH: &'static str = "assets/enemies.png";
+const FRAME_SIZE: u32 = 48;
+const FRAMES_PER_KIND: usize = 4;
+
+/// How far an enemy can stray outside the screen before it's deleted.
+const OFFSCREEN_MARGIN: f64 = 200.0;
+/// How long enemies are tinted for after taking a hit.
+const HURT_FLASH: u32 = 80;
+
+/// Decides how an enemy moves and when it fires. `bounds` is the enemy's own,
+/// which the behaviour is free to move.
+pub trait Behaviour {
+    fn act(
+        &mut self,
+        bounds: &mut BoundingRect,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<EntitySpec>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnemyKind {
+    Straight,
+    Weaver,
+    Chaser,
+    Turret,
+}
+
+pub static ALL_ENEMY_KINDS: [EnemyKind; 4] = [
+    EnemyKind::Straight,
+    EnemyKind::Weaver,
+    EnemyKind::Chaser,
+    EnemyKind::Turret,
+];
+
+impl EnemyKind {
+    /// Which row of the enemy spritesheet this kind's animation is on.
+    fn row(&self) -> usize {
+        match *self {
+            EnemyKind::Straight => 0,
+            EnemyKind::Weaver => 1,
+            EnemyKind::Chaser => 2,
+            EnemyKind::Turret => 3,
+        }
+    }
+
+    /// How much damage this kind takes before it's destroyed.
+    pub fn hp(&self) -> u32 {
+        match *self {
+            EnemyKind::Straight => 40,
+            EnemyKind::Weaver => 60,
+            EnemyKind::Chaser => 50,
+            EnemyKind::Turret => 150,
+        }
+    }
+
+    /// The path this kind follows unless told otherwise.
+    pub fn default_path(&self) -> Path {
+        match *self {
+            EnemyKind::Straight => Path::Straight { speed: 180.0 },
+            EnemyKind::Weaver => Path::Sine {
+                speed: 120.0,
+                amplitude: 60.0,
+                frequency: 2.5,
+            },
+            EnemyKind::Chaser => Path::Chase {
+                speed: 170.0,
+                turn_rate: 1.5,
+            },
+            EnemyKind::Turret => Path::Hold { distance: 200.0 },
+        }
+    }
+
+    pub fn spawn(
+        &self,
+        renderer: &mut Backend,
+        now: u32,
+        position: Vec2
+    ) -> SimpleObject {
+        self.spawn_on(renderer, now, position, self.default_path())
+    }
+
+    /// Spawns an enemy of this kind that follows `path` instead of its
+    /// default one.
+    pub fn spawn_on(
+        &self,
+        renderer: &mut Backend,
+        now: u32,
+        position: Vec2,
+        path: Path
+    ) -> SimpleObject {
+        let (kind, hp) = (*self, self.hp());
+
+        match path {
+            Path::Straight { speed } => box Enemy::new(
+                renderer, now, position, kind, hp,
+                StraightFlier::new(Vec2::new(-speed, 0.0))
+            ) as SimpleObject,
+            Path::Sine { speed, amplitude, frequency } => box Enemy::new(
+                renderer, now, position, kind, hp,
+                SineWeaver::new(position, speed, amplitude, frequency, now)
+            ) as SimpleObject,
+            Path::Chase { speed, turn_rate } => box Enemy::new(
+                renderer, now, position, kind, hp,
+                Chaser::new(Vec2::new(-speed, 0.0), speed, turn_rate)
+            ) as SimpleObject,
+            Path::Hold { distance } => box Enemy::new(
+                renderer, now, position, kind, hp,
+                Turret::new(position.x - distance, 120.0, turret_pattern())
+            ) as SimpleObject,
+        }
+    }
+}
+
+/// How an enemy moves once it has spawned. Any kind of enemy can follow any
+/// path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Path {
+    Straight {
+        speed: f64,
+    },
+    Sine {
+        speed: f64,
+        amplitude: f64,
+        frequency: f64,
+    },
+    Chase {
+        speed: f64,
+        turn_rate: f64,
+    },
+    /// Flies in `distance` pixels, then holds still and fires.
+    Hold {
+        distance: f64,
+    },
+}
+
+/// Aimed bursts, followed by a slow ring that blooms outwards.
+fn turret_pattern() -> PatternSequence {
+    PatternSequence::new(
+        vec![
+            Step::Fire(
+                Pattern::new(
+                    Shape::AimedFan {
+                        count: 3,
+                        spread: 0.4,
+                    }
+                ).with_bullet(
+                    BulletSpec {
+                        speed: 250.0,
+                        .. Default::default()
+                    }
+                ).repeat(3, 300)
+            ),
+            Step::Wait(600),
+            Step::Fire(
+                Pattern::new(
+                    Shape::Radial {
+                        count: 12,
+                        angle: 0.0,
+                    }
+                ).with_bullet(
+                    BulletSpec {
+                        speed: 60.0,
+                        acceleration: 120.0,
+                        max_speed: 220.0,
+                        delay: 300,
+                        .. Default::default()
+                    }
+                )
+            ),
+            Step::Wait(900),
+        ],
+        true
+    )
+}
+
+pub struct Enemy<B: Behaviour> {
+    pub kind: EnemyKind,
+    pub sprite: AnimatedSprite<u32, Texture>,
+    pub bounds: BoundingRect,
+    pub hp: u32,
+    pub hurt_until: u32,
+    pub behaviour: B,
+}
+
+impl<B: Behaviour> Enemy<B> {
+    pub fn new(
+        renderer: &mut Backend,
+        now: u32,
+        position: Vec2,
+        kind: EnemyKind,
+        hp: u32,
+        behaviour: B
+    ) -> Self {
+        Enemy {
+            kind: kind,
+            sprite: AnimatedSprite::from_spritesheet(
+                now,
+                8.0,
+                load_frames(renderer, kind)
+            ),
+            bounds: BoundingRect {
+                width: FRAME_SIZE as _,
+                height: FRAME_SIZE as _,
+                .. Default::default()
+            }.at(position),
+            hp: hp,
+            hurt_until: 0,
+            behaviour: behaviour,
+        }
+    }
+}
+
+fn load_frames(
+    renderer: &mut Backend,
+    kind: EnemyKind
+) -> Vec<Sprite<Texture>> {
+    build_spritesheet(
+        renderer.load_image(ENEMIES_PATH.as_ref()).unwrap(),
+        FRAME_SIZE,
+        FRAME_SIZE
+    ).into_iter()
+        .skip(kind.row() * FRAMES_PER_KIND)
+        .take(FRAMES_PER_KIND)
+        .collect()
+}
+
+impl<B: Behaviour> GameObject<Keys, Texture> for Enemy<B> {
+    fn update(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let bullets = self.behaviour.act(&mut self.bounds, context, time);
+
+        let area = context.play_area;
+
+        let out_of_play =
+            self.bounds.right() < area.left() - OFFSCREEN_MARGIN ||
+            self.bounds.left() > area.right() + OFFSCREEN_MARGIN ||
+            self.bounds.bottom() < area.top() - OFFSCREEN_MARGIN ||
+            self.bounds.top() > area.bottom() + OFFSCREEN_MARGIN;
+
+        let mut out = vec![];
+
+        if !bullets.is_empty() {
+            out.push(GameAction::SpawnEntities(bullets));
+        }
+
+        if out_of_play {
+            out.push(GameAction::Delete);
+        }
+
+        out
+    }
+
+    fn sprites(&self, time: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        let frame: VisibleComponent<_> =
+            self.sprite.frame(time.total).clone().into();
+
+        vec![
+            (
+                if time.total < self.hurt_until {
+                    frame.tinted(Color::RGB(255, 90, 90))
+                } else {
+                    frame
+                },
+                self.bounds.try_into().unwrap(),
+            )
+        ]
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let Vec2 { x, y } = self.bounds.center();
+
+        Some(
+            Circle {
+                x: x,
+                y: y,
+                radius: self.bounds.width * 0.4,
+            }.into()
+        )
+    }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Enemy }
+
+    fn receive_message<'a>(
+        &'a mut self,
+        ctx: &mut Context<Keys>,
+        time: GameTime,
+        msg: GameMessage<'a, Keys, Texture>
+    ) -> Vec<GameAction<Keys, Texture>> {
+        // Hits after the killing one in the same tick are ignored, so that
+        // the enemy only dies once
+        if self.hp == 0 { return vec![]; }
+
+        if let GameMessage::Hit {
+            info: DamageInfo { filter: DamageFilter::Player, damage },
+            ..
+        } = msg {
+            // Touching the ship doesn't hurt
+            if damage == 0 { return vec![]; }
+
+            if damage >= self.hp {
+                self.hp = 0;
+
+                let mut remains = ENEMY_DROPS.spawn(
+                    &mut ctx.renderer,
+                    time.total,
+                    self.bounds.center()
+                );
+
+                remains.push(
+                    box Explosion::with_bounds(
+                        &mut ctx.renderer,
+                        time.total,
+                        self.bounds
+                    )
+                );
+
+                vec![
+                    GameAction::AddObjects(remains),
+                    GameAction::Particles(
+                        vec![
+                            Emitter::at(
+                                &EXPLOSION_SPARKS,
+                                self.bounds.center()
+                            ),
+                        ]
+                    ),
+                    GameAction::ScreenEffects(
+                        vec![ScreenEffect::Shake(0.15)]
+                    ),
+                    GameAction::Delete,
+                ]
+            } else {
+                self.hp -= damage;
+                self.hurt_until = time.total + HURT_FLASH;
+
+                vec![]
+            }
+        } else {
+            vec![]
+        }
+    }
+
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        Some(
+            GameMessage::Hit {
+                other: self as _,
+                info: DamageInfo {
+                    damage: 30,
+                    filter: DamageFilter::Enemy,
+                },
+            }
+        )
+    }
+}
@@ -0,0 +1,393 @@
+use ::gameobjects::*;
+use ::gameobjects::ecs::EntitySpec;
+use ::events::*;
+
+use std::f64;
+use std::f64::consts::PI;
+
+use super::bullet::enemy_bullet;
+
+/// How each bullet of a pattern moves once it has been fired.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BulletSpec {
+    pub speed: f64,
+    /// Change in speed per second. Negative values slow bullets down until
+    /// they stop.
+    pub acceleration: f64,
+    pub max_speed: f64,
+    /// How long bullets hang in place before they start moving.
+    pub delay: u32,
+    pub damage: u32,
+}
+
+impl Default for BulletSpec {
+    fn default() -> Self {
+        BulletSpec {
+            speed: 200.0,
+            acceleration: 0.0,
+            max_speed: f64::INFINITY,
+            delay: 0,
+            damage: 10,
+        }
+    }
+}
+
+/// The directions the bullets of a single volley are fired in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    /// `count` bullets evenly spaced around a circle, the first going in the
+    /// direction `angle`.
+    Radial {
+        count: u32,
+        angle: f64,
+    },
+    /// Like `Radial`, but turning by `spin` radians with every volley.
+    Spiral {
+        count: u32,
+        angle: f64,
+        spin: f64,
+    },
+    /// `count` bullets spread evenly over `spread` radians, centered on the
+    /// target. With no target the fan points left, towards where the player
+    /// usually is.
+    AimedFan {
+        count: u32,
+        spread: f64,
+    },
+}
+
+impl Shape {
+    /// The angles of the bullets in volley number `volley`.
+    pub fn angles(
+        &self,
+        volley: u32,
+        origin: Vec2,
+        target: Option<Vec2>
+    ) -> Vec<f64> {
+        fn circle(count: u32, start: f64) -> Vec<f64> {
+            (0..count).map(|i|
+                start + 2.0 * PI * i as f64 / count as f64
+            ).collect()
+        }
+
+        match *self {
+            Shape::Radial { count, angle } => circle(count, angle),
+            Shape::Spiral { count, angle, spin } =>
+                circle(count, angle + spin * volley as f64),
+            Shape::AimedFan { count, spread } => {
+                let aim = target.map_or(PI, |t| (t - origin).angle());
+
+                if count <= 1 {
+                    vec![aim]
+                } else {
+                    let step = spread / (count - 1) as f64;
+
+                    (0..count).map(|i|
+                        aim - spread / 2.0 + step * i as f64
+                    ).collect()
+                }
+            },
+        }
+    }
+}
+
+/// A shape fired some number of times at a fixed interval.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pattern {
+    pub shape: Shape,
+    pub bullet: BulletSpec,
+    /// Time between the start of each volley.
+    pub interval: u32,
+    pub volleys: u32,
+}
+
+impl Pattern {
+    /// A single volley of `shape` with the default bullet.
+    pub fn new(shape: Shape) -> Self {
+        Pattern {
+            shape: shape,
+            bullet: Default::default(),
+            interval: 0,
+            volleys: 1,
+        }
+    }
+
+    pub fn with_bullet(self, bullet: BulletSpec) -> Self {
+        Pattern {
+            bullet: bullet,
+            .. self
+        }
+    }
+
+    /// Fires `volleys` volleys, `interval` milliseconds apart.
+    pub fn repeat(self, volleys: u32, interval: u32) -> Self {
+        Pattern {
+            volleys: volleys,
+            interval: interval,
+            .. self
+        }
+    }
+
+    /// How long it takes to fire every volley, including the wait after the
+    /// last one.
+    pub fn duration(&self) -> u32 {
+        self.interval * self.volleys
+    }
+
+    /// Fires volley number `volley` from `origin` at `now`.
+    pub fn fire(
+        &self,
+        volley: u32,
+        origin: Vec2,
+        target: Option<Vec2>,
+        now: u32
+    ) -> Vec<EntitySpec> {
+        self.shape.angles(volley, origin, target).into_iter().map(|angle|
+            enemy_bullet(origin, angle, &self.bullet, now)
+        ).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    Fire(Pattern),
+    Wait(u32),
+}
+
+impl Step {
+    pub fn duration(&self) -> u32 {
+        match *self {
+            Step::Fire(ref p) => p.duration(),
+            Step::Wait(d) => d,
+        }
+    }
+}
+
+/// A list of patterns and pauses that are run one after another. The clock
+/// starts on the first call to `update`, so an enemy can fly into position
+/// before it starts shooting.
+#[derive(Clone, Debug)]
+pub struct PatternSequence {
+    pub steps: Vec<Step>,
+    pub looping: bool,
+    current: usize,
+    step_started_at: Option<u32>,
+    volleys_fired: u32,
+}
+
+impl PatternSequence {
+    pub fn new(steps: Vec<Step>, looping: bool) -> Self {
+        PatternSequence {
+            steps: steps,
+            looping: looping,
+            current: 0,
+            step_started_at: None,
+            volleys_fired: 0,
+        }
+    }
+
+    /// Repeats a single pattern forever.
+    pub fn forever(pattern: Pattern) -> Self {
+        Self::new(vec![Step::Fire(pattern)], true)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.looping && self.current >= self.steps.len()
+    }
+
+    /// Starts again from the first step on the next `update`.
+    pub fn reset(&mut self) {
+        self.current = 0;
+        self.step_started_at = None;
+        self.volleys_fired = 0;
+    }
+
+    /// Fires every volley that has come due since the last call.
+    pub fn update(
+        &mut self,
+        origin: Vec2,
+        target: Option<Vec2>,
+        time: GameTime
+    ) -> Vec<EntitySpec> {
+        let mut out = vec![];
+        // A loop of steps that take no time would otherwise never finish
+        let mut steps_left = self.steps.len() + 1;
+
+        while steps_left > 0 {
+            if self.current >= self.steps.len() {
+                if self.looping && !self.steps.is_empty() {
+                    self.current = 0;
+                } else {
+                    break;
+                }
+            }
+
+            let started = self.step_started_at.unwrap_or(time.total);
+            let step = self.steps[self.current];
+
+            self.step_started_at = Some(started);
+
+            if let Step::Fire(ref pattern) = step {
+                while
+                    self.volleys_fired < pattern.volleys &&
+                    started + self.volleys_fired * pattern.interval <=
+                        time.total
+                {
+                    let fired_at =
+                        started + self.volleys_fired * pattern.interval;
+
+                    out.extend(
+                        pattern.fire(
+                            self.volleys_fired,
+                            origin,
+                            target,
+                            fired_at
+                        )
+                    );
+
+                    self.volleys_fired += 1;
+                }
+            }
+
+            if time.total - started < step.duration() {
+                break;
+            }
+
+            self.current += 1;
+            self.volleys_fired = 0;
+            self.step_started_at = Some(started + step.duration());
+            steps_left -= 1;
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pattern, PatternSequence, Shape, Step};
+    use ::gameobjects::Vec2;
+    use ::events::GameTime;
+
+    use std::f64::consts::PI;
+
+    fn at(total: u32) -> GameTime {
+        GameTime {
+            elapsed: 16,
+            total: total,
+        }
+    }
+
+    fn single() -> Pattern {
+        Pattern::new(Shape::Radial { count: 1, angle: 0.0 })
+    }
+
+    /// When each bullet fired by `sequence` at `total` was fired.
+    fn fire_times(sequence: &mut PatternSequence, total: u32) -> Vec<u32> {
+        sequence.update(Vec2::zero(), None, at(total)).into_iter()
+            .map(|spec| spec.launch_at.unwrap())
+            .collect()
+    }
+
+    fn assert_angles(actual: Vec<f64>, expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+
+        for (a, e) in actual.into_iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{} isn't {}", a, e);
+        }
+    }
+
+    #[test]
+    fn radial_and_spiral_shapes_go_all_the_way_round() {
+        let origin = Vec2::zero();
+        let spiral = Shape::Spiral { count: 2, angle: 0.0, spin: 0.1 };
+
+        assert_angles(
+            Shape::Radial { count: 4, angle: 0.0 }.angles(0, origin, None),
+            &[0.0, PI / 2.0, PI, PI * 1.5]
+        );
+        assert_angles(spiral.angles(2, origin, None), &[0.2, PI + 0.2]);
+    }
+
+    #[test]
+    fn fans_aim_at_the_target_or_left() {
+        let fan = Shape::AimedFan { count: 3, spread: 0.4 };
+        let origin = Vec2::zero();
+
+        assert_angles(fan.angles(0, origin, None), &[PI - 0.2, PI, PI + 0.2]);
+        assert_angles(
+            fan.angles(0, origin, Some(Vec2::new(10.0, 0.0))),
+            &[-0.2, 0.0, 0.2]
+        );
+        assert_angles(
+            Shape::AimedFan { count: 1, spread: 0.4 }.angles(0, origin, None),
+            &[PI]
+        );
+    }
+
+    #[test]
+    fn the_clock_starts_on_the_first_update() {
+        let mut sequence = PatternSequence::forever(single().repeat(2, 100));
+
+        assert_eq!(fire_times(&mut sequence, 1000), vec![1000]);
+        assert!(fire_times(&mut sequence, 1050).is_empty());
+        assert_eq!(fire_times(&mut sequence, 1100), vec![1100]);
+        // Looping starts the pattern again once its last interval is up
+        assert_eq!(fire_times(&mut sequence, 1200), vec![1200]);
+    }
+
+    #[test]
+    fn late_updates_fire_every_volley_that_was_due() {
+        let mut sequence = PatternSequence::new(
+            vec![Step::Fire(single().repeat(3, 100))],
+            false
+        );
+
+        assert_eq!(fire_times(&mut sequence, 0), vec![0]);
+        assert_eq!(fire_times(&mut sequence, 500), vec![100, 200]);
+        assert!(sequence.is_finished());
+        assert!(fire_times(&mut sequence, 1000).is_empty());
+    }
+
+    #[test]
+    fn waits_hold_back_the_next_step() {
+        let mut sequence = PatternSequence::new(
+            vec![Step::Wait(200), Step::Fire(single())],
+            false
+        );
+
+        assert!(fire_times(&mut sequence, 0).is_empty());
+        assert!(fire_times(&mut sequence, 199).is_empty());
+        assert!(!sequence.is_finished());
+        assert_eq!(fire_times(&mut sequence, 250), vec![200]);
+        assert!(sequence.is_finished());
+    }
+
+    #[test]
+    fn resetting_starts_again_from_the_first_step() {
+        let mut sequence = PatternSequence::new(
+            vec![Step::Fire(single().repeat(2, 100))],
+            false
+        );
+
+        fire_times(&mut sequence, 0);
+        fire_times(&mut sequence, 300);
+        assert!(sequence.is_finished());
+
+        sequence.reset();
+
+        assert!(!sequence.is_finished());
+        assert_eq!(fire_times(&mut sequence, 5000), vec![5000]);
+    }
+
+    #[test]
+    fn looping_steps_that_take_no_time_still_return() {
+        let mut sequence = PatternSequence::forever(single());
+
+        assert!(!fire_times(&mut sequence, 0).is_empty());
+        assert!(!PatternSequence::new(vec![], true).is_finished());
+        assert!(
+            fire_times(&mut PatternSequence::new(vec![], true), 0).is_empty()
+        );
+    }
+}
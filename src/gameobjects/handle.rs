@@ -0,0 +1,69 @@
+/// A reference to an object that stays valid while the object is moved around,
+/// and that can tell when the object it refers to has been deleted. Slots are
+/// reused, so the generation is what stops a stale handle from finding
+/// whatever object was put in its slot afterwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    pub fn index(&self) -> usize {
+        self.index as _
+    }
+}
+
+#[derive(Default)]
+pub struct HandleAllocator {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+}
+
+impl HandleAllocator {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn allocate(&mut self) -> Handle {
+        if let Some(index) = self.free.pop() {
+            self.alive[index as usize] = true;
+
+            Handle {
+                index: index,
+                generation: self.generations[index as usize],
+            }
+        } else {
+            self.generations.push(0);
+            self.alive.push(true);
+
+            Handle {
+                index: (self.generations.len() - 1) as _,
+                generation: 0,
+            }
+        }
+    }
+
+    /// Frees the slot of `handle`, after which it and every copy of it are
+    /// dead. Returns `false` if it was already dead.
+    pub fn release(&mut self, handle: Handle) -> bool {
+        if !self.is_alive(handle) { return false; }
+
+        let i = handle.index as usize;
+
+        self.alive[i] = false;
+        self.generations[i] = self.generations[i].wrapping_add(1);
+        self.free.push(handle.index);
+
+        true
+    }
+
+    pub fn is_alive(&self, handle: Handle) -> bool {
+        let i = handle.index as usize;
+
+        i < self.generations.len() &&
+        self.alive[i] &&
+        self.generations[i] == handle.generation
+    }
+}
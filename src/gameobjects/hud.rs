@@ -0,0 +1,244 @@
+use ::gameobjects::*;
+use ::gameobjects::player::GunStatus;
+use ::graphics::sprites::{Sprite, VisibleComponent, VisibleRect};
+use ::graphics::backend::{Backend, Texture};
+
+use std::convert::TryInto;
+use sdl2::pixels::Color;
+
+const HUD_FONT: &'static str = "assets/belligerent.ttf";
+const HUD_FONT_SIZE: u16 = 18;
+const MARGIN: f64 = 10.0;
+const BAR_SIZE: [f64; 2] = [150.0, 8.0];
+/// The boss's health bar is this wide, as a fraction of the screen.
+const BOSS_BAR_WIDTH: f64 = 0.6;
+const BOSS_BAR_HEIGHT: f64 = 10.0;
+
+/// Everything about the player that the HUD shows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerStatus {
+    pub hp: u32,
+    pub max_hp: u32,
+    pub shield: u32,
+    pub max_shield: u32,
+    pub lives: u32,
+    pub score: u32,
+    pub bombs: u32,
+    /// See `TimedShield::gauge`.
+    pub timed_shield: f64,
+    pub timed_shield_active: bool,
+    pub gun: GunStatus,
+}
+
+/// Draws the player's status over the top of the game, along with the
+/// health of any boss. Text is only rendered again when it changes.
+#[derive(Default)]
+pub struct Hud {
+    status: Option<PlayerStatus>,
+    boss_health: Option<f64>,
+    labels: Vec<(String, Sprite<Texture>)>,
+    screen_width: f64,
+    screen_height: f64,
+}
+
+impl Hud {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn lines(status: &PlayerStatus) -> Vec<String> {
+        vec![
+            format!(
+                "Lives {}   Bombs {}   Score {}",
+                status.lives,
+                status.bombs,
+                status.score
+            ),
+            format!(
+                "{} Lv {}/{}",
+                status.gun.name,
+                status.gun.level + 1,
+                status.gun.max_level + 1
+            ),
+            status.gun.details.clone(),
+        ]
+    }
+
+    /// Lays the HUD out on `screen`, which should be the logical screen
+    /// rather than the window. `boss_health` is a fraction, as given by
+    /// `GameObject::boss_health`.
+    pub fn update(
+        &mut self,
+        renderer: &mut Backend,
+        screen: Dest,
+        status: Option<PlayerStatus>,
+        boss_health: Option<f64>
+    ) {
+        self.screen_width = screen.width as _;
+        self.screen_height = screen.height as _;
+        self.boss_health = boss_health;
+
+        let lines = status.as_ref().map_or(vec![], Self::lines);
+
+        self.labels.truncate(lines.len());
+
+        for (i, line) in lines.into_iter().enumerate() {
+            if self.labels.get(i).map_or(false, |&(ref l, _)| *l == line) {
+                continue;
+            }
+
+            let sprite = renderer.render_text(
+                HUD_FONT,
+                HUD_FONT_SIZE,
+                &line,
+                Color::RGB(230, 230, 230)
+            ).ok().map(Sprite::new);
+
+            if let Some(sprite) = sprite {
+                if i < self.labels.len() {
+                    self.labels[i] = (line, sprite);
+                } else {
+                    self.labels.push((line, sprite));
+                }
+            }
+        }
+
+        self.status = status;
+    }
+
+    fn bar(
+        y: f64,
+        fraction: f64,
+        back: Color,
+        fill: Color
+    ) -> Vec<(VisibleComponent<Texture>, Dest)> {
+        let [w, h] = BAR_SIZE;
+
+        Self::bar_at(
+            BoundingRect {
+                x: MARGIN,
+                y: y,
+                width: w,
+                height: h,
+            },
+            fraction,
+            back,
+            fill
+        )
+    }
+
+    fn bar_at(
+        back_rect: BoundingRect,
+        fraction: f64,
+        back: Color,
+        fill: Color
+    ) -> Vec<(VisibleComponent<Texture>, Dest)> {
+        let w = back_rect.width;
+
+        let mut out = vec![
+            (VisibleRect(back).into(), back_rect.try_into().unwrap()),
+        ];
+
+        if fraction > 0.0 {
+            out.push(
+                (
+                    VisibleRect(fill).into(),
+                    BoundingRect {
+                        width: w * fraction.min(1.0),
+                        .. back_rect
+                    }.try_into().unwrap(),
+                )
+            );
+        }
+
+        out
+    }
+
+    fn label(&self, i: usize, x: f64, y: f64)
+        -> Option<(VisibleComponent<Texture>, Dest)>
+    {
+        self.labels.get(i).map(|&(_, ref sprite)|
+            (
+                sprite.clone().into(),
+                Dest {
+                    x: x as _,
+                    y: y as _,
+                    width: sprite.mask.width,
+                    height: sprite.mask.height,
+                },
+            )
+        )
+    }
+
+    fn boss_bar(&self) -> Vec<(VisibleComponent<Texture>, Dest)> {
+        let fraction = if let Some(f) = self.boss_health {
+            f
+        } else {
+            return vec![];
+        };
+
+        let width = self.screen_width * BOSS_BAR_WIDTH;
+
+        Self::bar_at(
+            BoundingRect {
+                x: (self.screen_width - width) / 2.0,
+                y: MARGIN,
+                width: width,
+                height: BOSS_BAR_HEIGHT,
+            },
+            fraction,
+            Color::RGB(60, 20, 20),
+            Color::RGB(220, 40, 40)
+        )
+    }
+
+    pub fn sprites(&self) -> Vec<(VisibleComponent<Texture>, Dest)> {
+        let mut out = self.boss_bar();
+
+        let status = if let Some(ref s) = self.status {
+            s
+        } else {
+            return out;
+        };
+
+        let [_, bar_h] = BAR_SIZE;
+        let line_h = HUD_FONT_SIZE as f64 + 4.0;
+        let bottom = self.screen_height - MARGIN;
+
+        out.extend(
+            Self::bar(
+                MARGIN,
+                status.hp as f64 / status.max_hp as f64,
+                Color::RGB(40, 60, 40),
+                Color::RGB(60, 210, 80)
+            )
+        );
+        out.extend(
+            Self::bar(
+                MARGIN + bar_h + 2.0,
+                status.shield as f64 / status.max_shield as f64,
+                Color::RGB(30, 40, 70),
+                Color::RGB(70, 140, 255)
+            )
+        );
+        out.extend(
+            Self::bar(
+                MARGIN + 2.0 * (bar_h + 2.0),
+                status.timed_shield,
+                Color::RGB(40, 40, 50),
+                if status.timed_shield_active {
+                    Color::RGB(230, 240, 255)
+                } else if status.timed_shield >= 1.0 {
+                    Color::RGB(110, 190, 255)
+                } else {
+                    Color::RGB(90, 100, 130)
+                }
+            )
+        );
+        out.extend(self.label(0, MARGIN, MARGIN + 3.0 * bar_h + 10.0));
+        out.extend(self.label(1, MARGIN, bottom - 2.0 * line_h));
+        out.extend(self.label(2, MARGIN, bottom - line_h));
+
+        out
+    }
+}
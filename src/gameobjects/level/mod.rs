@@ -0,0 +1,342 @@
+use ::gameobjects::*;
+use ::gameobjects::player::{Asteroid, SimpleObject};
+use ::gameobjects::enemies::{EnemyKind, Path};
+use ::gameobjects::enemies::boss::Boss;
+use ::gameobjects::object_table::ObjectTable;
+use ::events::*;
+use ::time::*;
+use ::graphics::backend::Backend;
+
+use std::fs::File;
+use std::io::Read;
+
+pub mod parse;
+
+/// When an event happens. Events are run in order, so an event never happens
+/// before the one above it in the script, whatever its trigger says.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trigger {
+    /// Milliseconds of level time since the level started.
+    At(u32),
+    /// Milliseconds of level time since the previous event.
+    After(u32),
+    /// Pixels scrolled since the level started.
+    Distance(f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpawnKind {
+    Enemy(EnemyKind),
+    Asteroid,
+    /// Comes to rest centered on its entry position.
+    Boss,
+}
+
+/// How a group spawned by a single event is arranged around its entry
+/// position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Formation {
+    Single,
+    /// One behind the other, following the same path.
+    Line {
+        count: u32,
+        spacing: f64,
+    },
+    /// Side by side, centered on the entry position.
+    Column {
+        count: u32,
+        spacing: f64,
+    },
+    /// A leader with the rest trailing behind it above and below.
+    Vee {
+        count: u32,
+        spacing: f64,
+    },
+}
+
+impl Formation {
+    /// The offset of each member of the formation from the entry position.
+    pub fn offsets(&self) -> Vec<Vec2> {
+        match *self {
+            Formation::Single => vec![Vec2::zero()],
+            Formation::Line { count, spacing } => (0..count).map(|i|
+                Vec2::new(i as f64 * spacing, 0.0)
+            ).collect(),
+            Formation::Column { count, spacing } => (0..count).map(|i|
+                Vec2::new(
+                    0.0,
+                    (i as f64 - (count - 1) as f64 / 2.0) * spacing
+                )
+            ).collect(),
+            Formation::Vee { count, spacing } => (0..count).map(|i| {
+                let rank = ((i + 1) / 2) as f64;
+                let side = if i % 2 == 0 { 1.0 } else { -1.0 };
+
+                Vec2::new(rank * spacing, side * rank * spacing)
+            }).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spawn {
+    pub kind: SpawnKind,
+    /// Where the group enters, as a fraction of the screen size.
+    pub position: Vec2,
+    /// Overrides the default path of enemies.
+    pub path: Option<Path>,
+    pub formation: Formation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    Spawn(Spawn),
+    /// Spawns an asteroid at a random height every so many milliseconds, or
+    /// stops doing so.
+    Asteroids(Option<u32>),
+    /// Sets the scroll speed in pixels per second, used by `Distance`
+    /// triggers.
+    Speed(f64),
+    /// Stops the level for some milliseconds.
+    Wait(u32),
+    /// Stops the level until every enemy is gone.
+    WaitClear,
+    /// Where the level restarts from when the player runs out of lives.
+    Checkpoint,
+    End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Event {
+    pub trigger: Trigger,
+    pub command: Command,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    pub events: Vec<Event>,
+}
+
+impl Level {
+    pub fn load<P: AsRef<::std::path::Path>>(
+        path: P
+    ) -> Result<Level, String> {
+        let mut src = String::new();
+
+        try!(
+            File::open(path.as_ref())
+                .and_then(|mut f| f.read_to_string(&mut src))
+                .map_err(|e| e.to_string())
+        );
+
+        src.parse()
+    }
+}
+
+const DEFAULT_SCROLL_SPEED: f64 = 80.0;
+
+/// The state of the level at a checkpoint, enough to start the level again
+/// from there.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Checkpoint {
+    next: usize,
+    clock: u32,
+    distance: f64,
+    scroll_speed: f64,
+    asteroid_interval: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Pause {
+    Running,
+    For(u32),
+    UntilClear,
+}
+
+/// Runs a `Level`'s script, spawning its objects as their events come due.
+/// The level's clock and scrolling stop while it's paused.
+pub struct LevelDirector {
+    level: Level,
+    next: usize,
+    clock: u32,
+    distance: f64,
+    scroll_speed: f64,
+    last_event_at: u32,
+    pause: Pause,
+    asteroid_interval: Option<u32>,
+    last_asteroid_at: u32,
+    checkpoint: Option<Checkpoint>,
+    finished: bool,
+}
+
+impl LevelDirector {
+    pub fn new(level: Level) -> Self {
+        LevelDirector {
+            level: level,
+            next: 0,
+            clock: 0,
+            distance: 0.0,
+            scroll_speed: DEFAULT_SCROLL_SPEED,
+            last_event_at: 0,
+            pause: Pause::Running,
+            asteroid_interval: None,
+            last_asteroid_at: 0,
+            checkpoint: None,
+            finished: false,
+        }
+    }
+
+    pub fn from_checkpoint(level: Level, checkpoint: Checkpoint) -> Self {
+        LevelDirector {
+            next: checkpoint.next,
+            clock: checkpoint.clock,
+            distance: checkpoint.distance,
+            scroll_speed: checkpoint.scroll_speed,
+            last_event_at: checkpoint.clock,
+            asteroid_interval: checkpoint.asteroid_interval,
+            last_asteroid_at: checkpoint.clock,
+            checkpoint: Some(checkpoint),
+            .. LevelDirector::new(level)
+        }
+    }
+
+    /// The last checkpoint passed, if any.
+    pub fn checkpoint(&self) -> Option<Checkpoint> {
+        self.checkpoint
+    }
+
+    /// Whether the level has reached its `end`.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause != Pause::Running
+    }
+
+    fn is_due(&self, trigger: Trigger) -> bool {
+        match trigger {
+            Trigger::At(t) => self.clock >= t,
+            Trigger::After(t) => self.clock >= self.last_event_at + t,
+            Trigger::Distance(d) => self.distance >= d,
+        }
+    }
+
+    /// Moves the level on by `time.elapsed`, returning anything that it
+    /// spawns. Spawn positions are given relative to `play_area`.
+    pub fn update(
+        &mut self,
+        renderer: &mut Backend,
+        objects: &ObjectTable,
+        play_area: BoundingRect,
+        time: GameTime
+    ) -> Vec<SimpleObject> {
+        use rand::random;
+
+        let mut out = vec![];
+
+        if self.finished { return out; }
+
+        match self.pause {
+            Pause::Running => {},
+            Pause::For(left) => if left > time.elapsed {
+                self.pause = Pause::For(left - time.elapsed);
+
+                return out;
+            },
+            Pause::UntilClear =>
+                if objects.iter().any(|info| info.holds_up_level) {
+                    return out;
+                },
+        }
+
+        self.pause = Pause::Running;
+        self.clock += time.elapsed;
+        self.distance += self.scroll_speed * time.elapsed.exact_seconds();
+
+        if let Some(interval) = self.asteroid_interval {
+            if self.clock - self.last_asteroid_at > interval {
+                out.push(
+                    box Asteroid::new(
+                        renderer,
+                        time.total,
+                        Vec2::new(
+                            play_area.right(),
+                            play_area.top() +
+                                random::<f64>() * (play_area.height - 96.0)
+                        )
+                    ) as SimpleObject
+                );
+
+                self.last_asteroid_at = self.clock;
+            }
+        }
+
+        while
+            !self.finished &&
+            self.pause == Pause::Running &&
+            self.next < self.level.events.len()
+        {
+            let event = self.level.events[self.next];
+
+            if !self.is_due(event.trigger) { break; }
+
+            self.next += 1;
+            self.last_event_at = self.clock;
+
+            match event.command {
+                Command::Spawn(spawn) => out.extend(
+                    Self::spawn(renderer, time.total, play_area, &spawn)
+                ),
+                Command::Asteroids(interval) => {
+                    self.asteroid_interval = interval;
+                    self.last_asteroid_at = self.clock;
+                },
+                Command::Speed(speed) => self.scroll_speed = speed,
+                Command::Wait(duration) => self.pause = Pause::For(duration),
+                Command::WaitClear => self.pause = Pause::UntilClear,
+                Command::Checkpoint => self.checkpoint = Some(
+                    Checkpoint {
+                        next: self.next,
+                        clock: self.clock,
+                        distance: self.distance,
+                        scroll_speed: self.scroll_speed,
+                        asteroid_interval: self.asteroid_interval,
+                    }
+                ),
+                Command::End => self.finished = true,
+            }
+        }
+
+        out
+    }
+
+    fn spawn(
+        renderer: &mut Backend,
+        now: u32,
+        play_area: BoundingRect,
+        spawn: &Spawn
+    ) -> Vec<SimpleObject> {
+        let entry = play_area.position() + Vec2::new(
+            spawn.position.x * play_area.width,
+            spawn.position.y * play_area.height
+        );
+
+        spawn.formation.offsets().into_iter().map(|offset| {
+            let position = entry + offset;
+
+            match (spawn.kind, spawn.path) {
+                (SpawnKind::Enemy(kind), Some(path)) =>
+                    kind.spawn_on(renderer, now, position, path),
+                (SpawnKind::Enemy(kind), None) =>
+                    kind.spawn(renderer, now, position),
+                (SpawnKind::Asteroid, _) =>
+                    box Asteroid::new(renderer, now, position)
+                        as SimpleObject,
+                (SpawnKind::Boss, _) =>
+                    box Boss::new(renderer, now, position, play_area)
+                        as SimpleObject,
+            }
+        }).collect()
+    }
+}
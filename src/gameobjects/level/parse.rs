@@ -0,0 +1,329 @@
+use ::gameobjects::Vec2;
+use ::gameobjects::enemies::{EnemyKind, Path};
+
+use super::*;
+
+use std::str::{FromStr, SplitWhitespace};
+
+struct Tokens<'a>(SplitWhitespace<'a>);
+
+impl<'a> Tokens<'a> {
+    fn word(&mut self, what: &str) -> Result<&'a str, String> {
+        self.0.next().ok_or_else(|| format!("expected {}", what))
+    }
+
+    fn number<T: FromStr>(&mut self, what: &str) -> Result<T, String> {
+        let word = try!(self.word(what));
+
+        word.parse().map_err(|_|
+            format!("expected {}, found \"{}\"", what, word)
+        )
+    }
+}
+
+fn parse_trigger(tokens: &mut Tokens) -> Result<Trigger, String> {
+    match try!(tokens.word("a trigger")) {
+        "at" => Ok(Trigger::At(try!(tokens.number("a time")))),
+        "after" => Ok(Trigger::After(try!(tokens.number("a time")))),
+        "distance" =>
+            Ok(Trigger::Distance(try!(tokens.number("a distance")))),
+        other => Err(format!("unknown trigger \"{}\"", other)),
+    }
+}
+
+fn parse_kind(word: &str) -> Result<SpawnKind, String> {
+    match word {
+        "asteroid" => Ok(SpawnKind::Asteroid),
+        "boss" => Ok(SpawnKind::Boss),
+        "straight" => Ok(SpawnKind::Enemy(EnemyKind::Straight)),
+        "weaver" => Ok(SpawnKind::Enemy(EnemyKind::Weaver)),
+        "chaser" => Ok(SpawnKind::Enemy(EnemyKind::Chaser)),
+        "turret" => Ok(SpawnKind::Enemy(EnemyKind::Turret)),
+        other => Err(format!("unknown kind \"{}\"", other)),
+    }
+}
+
+fn parse_path(tokens: &mut Tokens) -> Result<Path, String> {
+    match try!(tokens.word("a path")) {
+        "straight" => Ok(
+            Path::Straight {
+                speed: try!(tokens.number("a speed")),
+            }
+        ),
+        "sine" => Ok(
+            Path::Sine {
+                speed: try!(tokens.number("a speed")),
+                amplitude: try!(tokens.number("an amplitude")),
+                frequency: try!(tokens.number("a frequency")),
+            }
+        ),
+        "chase" => Ok(
+            Path::Chase {
+                speed: try!(tokens.number("a speed")),
+                turn_rate: try!(tokens.number("a turn rate")),
+            }
+        ),
+        "hold" => Ok(
+            Path::Hold {
+                distance: try!(tokens.number("a distance")),
+            }
+        ),
+        other => Err(format!("unknown path \"{}\"", other)),
+    }
+}
+
+fn parse_formation(tokens: &mut Tokens) -> Result<Formation, String> {
+    let shape = try!(tokens.word("a formation"));
+    let count = try!(tokens.number("a count"));
+    let spacing = try!(tokens.number("a spacing"));
+
+    match shape {
+        "line" => Ok(Formation::Line { count: count, spacing: spacing }),
+        "column" => Ok(Formation::Column { count: count, spacing: spacing }),
+        "vee" => Ok(Formation::Vee { count: count, spacing: spacing }),
+        other => Err(format!("unknown formation \"{}\"", other)),
+    }
+}
+
+fn parse_spawn(tokens: &mut Tokens) -> Result<Spawn, String> {
+    let mut spawn = Spawn {
+        kind: try!(parse_kind(try!(tokens.word("a kind")))),
+        position: Vec2::new(1.0, 0.5),
+        path: None,
+        formation: Formation::Single,
+    };
+
+    while let Some(option) = tokens.0.next() {
+        match option {
+            "x" => spawn.position.x = try!(tokens.number("a fraction")),
+            "y" => spawn.position.y = try!(tokens.number("a fraction")),
+            "path" => spawn.path = Some(try!(parse_path(tokens))),
+            "formation" => spawn.formation = try!(parse_formation(tokens)),
+            other => return Err(format!("unknown option \"{}\"", other)),
+        }
+    }
+
+    Ok(spawn)
+}
+
+fn parse_command(tokens: &mut Tokens) -> Result<Command, String> {
+    match try!(tokens.word("a command")) {
+        "spawn" => Ok(Command::Spawn(try!(parse_spawn(tokens)))),
+        "asteroids" => match try!(tokens.word("\"every\" or \"off\"")) {
+            "every" => Ok(
+                Command::Asteroids(Some(try!(tokens.number("a time"))))
+            ),
+            "off" => Ok(Command::Asteroids(None)),
+            other => Err(
+                format!("expected \"every\" or \"off\", found \"{}\"", other)
+            ),
+        },
+        "speed" => Ok(Command::Speed(try!(tokens.number("a speed")))),
+        "wait" => match try!(tokens.word("a time or \"clear\"")) {
+            "clear" => Ok(Command::WaitClear),
+            time => time.parse().map(Command::Wait).map_err(|_|
+                format!("expected a time or \"clear\", found \"{}\"", time)
+            ),
+        },
+        "checkpoint" => Ok(Command::Checkpoint),
+        "end" => Ok(Command::End),
+        other => Err(format!("unknown command \"{}\"", other)),
+    }
+}
+
+fn parse_event(line: &str) -> Result<Event, String> {
+    let mut tokens = Tokens(line.split_whitespace());
+
+    let event = Event {
+        trigger: try!(parse_trigger(&mut tokens)),
+        command: try!(parse_command(&mut tokens)),
+    };
+
+    if let Some(extra) = tokens.0.next() {
+        Err(format!("unexpected \"{}\"", extra))
+    } else {
+        Ok(event)
+    }
+}
+
+/// Parses a level script. See `assets/levels/level1.lvl` for the format.
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Level, String> {
+        let mut events = vec![];
+
+        for (i, line) in src.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+
+            if line.trim().is_empty() { continue; }
+
+            events.push(
+                try!(
+                    parse_event(line).map_err(|e|
+                        format!("line {}: {}", i + 1, e)
+                    )
+                )
+            );
+        }
+
+        Ok(Level { events: events })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::gameobjects::Vec2;
+    use ::gameobjects::enemies::{EnemyKind, Path};
+    use ::gameobjects::level::{
+        Command,
+        Event,
+        Formation,
+        Level,
+        Spawn,
+        SpawnKind,
+        Trigger,
+    };
+
+    fn parse(src: &str) -> Result<Vec<Event>, String> {
+        src.parse::<Level>().map(|level| level.events)
+    }
+
+    #[test]
+    fn every_trigger_parses() {
+        assert_eq!(
+            parse("at 100 end\nafter 250 end\ndistance 1.5 end"),
+            Ok(
+                vec![
+                    Event { trigger: Trigger::At(100), command: Command::End },
+                    Event {
+                        trigger: Trigger::After(250),
+                        command: Command::End,
+                    },
+                    Event {
+                        trigger: Trigger::Distance(1.5),
+                        command: Command::End,
+                    },
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn every_command_parses() {
+        let commands = parse(
+            "at 0 asteroids every 1500\n\
+             at 0 asteroids off\n\
+             at 0 speed 120\n\
+             at 0 wait 500\n\
+             at 0 wait clear\n\
+             at 0 checkpoint\n\
+             at 0 end"
+        ).unwrap().into_iter().map(|e| e.command).collect::<Vec<_>>();
+
+        assert_eq!(
+            commands,
+            vec![
+                Command::Asteroids(Some(1500)),
+                Command::Asteroids(None),
+                Command::Speed(120.0),
+                Command::Wait(500),
+                Command::WaitClear,
+                Command::Checkpoint,
+                Command::End,
+            ]
+        );
+    }
+
+    #[test]
+    fn spawns_default_to_a_single_one_on_the_right() {
+        assert_eq!(
+            parse("at 0 spawn boss").unwrap()[0].command,
+            Command::Spawn(
+                Spawn {
+                    kind: SpawnKind::Boss,
+                    position: Vec2::new(1.0, 0.5),
+                    path: None,
+                    formation: Formation::Single,
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn spawn_options_can_come_in_any_order() {
+        let spawn = Spawn {
+            kind: SpawnKind::Enemy(EnemyKind::Weaver),
+            position: Vec2::new(0.8, 0.25),
+            path: Some(
+                Path::Sine {
+                    speed: 100.0,
+                    amplitude: 40.0,
+                    frequency: 2.0,
+                }
+            ),
+            formation: Formation::Vee { count: 5, spacing: 30.0 },
+        };
+
+        assert_eq!(
+            parse(
+                "at 0 spawn weaver x 0.8 y 0.25 path sine 100 40 2 \
+                 formation vee 5 30"
+            ).unwrap()[0].command,
+            Command::Spawn(spawn)
+        );
+        assert_eq!(
+            parse(
+                "at 0 spawn weaver formation vee 5 30 \
+                 path sine 100 40 2 y 0.25 x 0.8"
+            ).unwrap()[0].command,
+            Command::Spawn(spawn)
+        );
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        assert_eq!(
+            parse("# A level\n\n   \nat 10 end # the end\n# done"),
+            Ok(vec![Event { trigger: Trigger::At(10), command: Command::End }])
+        );
+    }
+
+    #[test]
+    fn errors_say_which_line_they_are_on() {
+        assert_eq!(
+            parse("at 0 end\n\nsoon end"),
+            Err("line 3: unknown trigger \"soon\"".to_string())
+        );
+        assert_eq!(
+            parse("at 0 spawn dragon"),
+            Err("line 1: unknown kind \"dragon\"".to_string())
+        );
+        assert_eq!(
+            parse("at 0 spawn straight path chase 100"),
+            Err("line 1: expected a turn rate".to_string())
+        );
+        assert_eq!(
+            parse("at soon end"),
+            Err("line 1: expected a time, found \"soon\"".to_string())
+        );
+        assert_eq!(
+            parse("at 0 wait forever"),
+            Err(
+                "line 1: expected a time or \"clear\", found \"forever\""
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            parse("at 0 end now"),
+            Err("line 1: unexpected \"now\"".to_string())
+        );
+    }
+
+    #[test]
+    fn the_shipped_level_parses() {
+        let level = Level::load("assets/levels/level1.lvl").unwrap();
+
+        assert!(!level.events.is_empty());
+    }
+}
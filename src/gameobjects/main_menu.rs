@@ -2,18 +2,16 @@ use ::time::TimeExtensions;
 use ::coalesce::Coalesce;
 use ::events::Keys;
 use ::view::{Context, View, ViewBuilder, Action};
-use ::graphics::sprites::{LoadSprite, CopyRenderable, Sprite, VisibleComponent};
-use ::graphics::font_cache::FontCache;
+use ::graphics::backend::{Backend, Texture};
+use ::graphics::sprites::{LoadSprite, Sprite, VisibleComponent};
 use ::gameobjects::background::ParallaxSet;
 use ::gameobjects::player::*;
-use ::gameobjects::Dest;
+use ::gameobjects::{Dest, Vec2};
 
 use std::marker::PhantomData;
-use sdl2_ttf::Font;
-use sdl2::render::{Texture, Renderer};
 use sdl2::pixels::Color;
 
-pub type Background = [([f64; 2], [f64; 2], Sprite<Texture>); 3];
+pub type Background = [(Vec2, Vec2, Sprite<Texture>); 3];
 
 pub struct Menu<
     Time: TimeExtensions,
@@ -22,7 +20,7 @@ pub struct Menu<
     T
 > where
     //for<'a> &'a mut I: IntoIterator<Item=&'a mut MenuItem<T>>,
-    for<'a> &'a B: IntoIterator<Item=&'a ([f64; 2], [f64; 2], Sprite<Texture>)>
+    for<'a> &'a B: IntoIterator<Item=&'a (Vec2, Vec2, Sprite<Texture>)>
 {
     pub items: I,
     background: ParallaxSet<Time, Texture, B>,
@@ -38,33 +36,28 @@ impl<'any, I, B> View<Keys, VisibleComponent<Texture>>
         for<'a> &'a mut I: IntoIterator<
             Item=&'a mut MenuItem<Action<'any, Keys, VisibleComponent<Texture>>>
         >,
-        for<'a> &'a B: IntoIterator<Item=&'a ([f64; 2], [f64; 2], Sprite<Texture>)>
+        for<'a> &'a B: IntoIterator<Item=&'a (Vec2, Vec2, Sprite<Texture>)>
 {
     fn update(
         &mut self,
         context: &mut Context<Keys>,
         elapsed: u32
     ) -> Action<Keys, VisibleComponent<Texture>> {
-        context.renderer.set_draw_color(Color::RGB(0, 0, 0));
-        context.renderer.clear();
+        context.renderer.clear_to(Color::RGB(0, 0, 0));
 
         self.total_time += elapsed;
 
-        let (screen_w, screen_h) = context.renderer.output_size().unwrap();
+        let screen = context.screen;
+        let (screen_w, screen_h) = (screen.width, screen.height);
 
         let y_gutter = 70;
         let y_offset = (screen_h as usize - y_gutter * self.count) / 2;
 
-        let screen = Dest::default().with_size(screen_w, screen_h);
-
         for (sprite, dest) in self.background.get_destinations(
             screen,
             self.total_time
         ) {
-            context.renderer.copy_renderable(
-                &sprite,
-                dest
-            );
+            context.renderer.draw(&VisibleComponent::Sprite(sprite), dest);
         }
 
         if context.events.down.fire {
@@ -117,11 +110,11 @@ impl<'any, I, B> View<Keys, VisibleComponent<Texture>>
 }
 
 impl<Time: TimeExtensions + Copy + Default, I, T>
-    Menu<Time, I, [([f64; 2], [f64; 2], Sprite<Texture>); 3], T>
+    Menu<Time, I, [(Vec2, Vec2, Sprite<Texture>); 3], T>
     where
         for<'a> &'a mut I: IntoIterator<Item=&'a mut MenuItem<T>>,
 {
-    pub fn new(renderer: &mut Renderer, mut items: I) -> Self {
+    pub fn new(renderer: &mut Backend, mut items: I) -> Self {
         let count = (&mut items).into_iter().count();
 
         Menu {
@@ -129,22 +122,22 @@ impl<Time: TimeExtensions + Copy + Default, I, T>
             background: ParallaxSet::new(
                 [
                     (
-                        [-200.0, 0.0],
-                        [0.0, 0.0],
+                        Vec2::new(-200.0, 0.0),
+                        Vec2::new(0.0, 0.0),
                         renderer.load_sprite(
                             "assets/spaceBG.png"
                         ).unwrap()
                     ),
                     (
-                        [-400.0, 0.0],
-                        [0.0, 30.0],
+                        Vec2::new(-400.0, 0.0),
+                        Vec2::new(0.0, 30.0),
                         renderer.load_sprite(
                             "assets/spaceFG.png"
                         ).unwrap()
                     ),
                     (
-                        [-500.0, 0.0],
-                        [0.0, 0.0],
+                        Vec2::new(-500.0, 0.0),
+                        Vec2::new(0.0, 0.0),
                         renderer.load_sprite(
                             "assets/spaceFG.png"
                         ).unwrap()
@@ -166,25 +159,24 @@ pub struct MenuItem<T> {
     on_select: Option<T>,
 }
 
+const MENU_FONT: &'static str = "assets/belligerent.ttf";
+
 fn get_sprites(
-    renderer: &mut Renderer,
-    text: &str,
-    fonts: (&Font, &Font)
+    renderer: &mut Backend,
+    text: &str
 ) -> (Sprite<Texture>, Sprite<Texture>) {
     let (idle_color, hover_color) = (
         Color::RGB(120, 120, 120),
         Color::RGB(255, 255, 255),
     );
 
-    let color_to_sprite = |color, font: &Font|
-        font.render(text).blended(color).ok()
-            .and_then(
-                |surface| renderer.create_texture_from_surface(&surface).ok()
-            ).map(Sprite::new);
+    let mut color_to_sprite = |color, size|
+        renderer.render_text(MENU_FONT, size, text, color).ok()
+            .map(Sprite::new);
 
     (
-        color_to_sprite(idle_color, fonts.0),
-        color_to_sprite(hover_color, fonts.1),
+        color_to_sprite(idle_color, 32),
+        color_to_sprite(hover_color, 38),
     ).coalesce().unwrap()
 }
 
@@ -210,7 +202,7 @@ impl ViewBuilder<Keys, VisibleComponent<Texture>> for MainMenuBuilder {
         -> Box<View<Keys, VisibleComponent<Texture>>>
     {
         Box::new(
-            main_menu(context.renderer, context.font_cache, box ShipViewBuilder)
+            main_menu(context.renderer, box ShipViewBuilder)
         )
     }
 }
@@ -229,7 +221,6 @@ impl ViewBuilder<Keys, VisibleComponent<Texture>>
         Box::new(
             main_menu(
                 context.renderer,
-                context.font_cache,
                 box move |_: &mut Context<Keys>| next
             )
         )
@@ -237,8 +228,7 @@ impl ViewBuilder<Keys, VisibleComponent<Texture>>
 }
 
 pub fn main_menu<'a>(
-    renderer: &mut Renderer,
-    cache: &mut FontCache,
+    renderer: &mut Backend,
     view: Box<ViewBuilder<Keys, VisibleComponent<Texture>>>
 ) -> Menu<
     u32,
@@ -246,18 +236,9 @@ pub fn main_menu<'a>(
     Background,
     Action<'a, Keys, VisibleComponent<Texture>>
 > {
-    let path = "assets/belligerent.ttf";
-    let cache = cache.with_loaded(path, 38)
-        .and_then(|c| c.with_loaded(path, 32))
-        .unwrap();
-
-    let font0 = cache.get(path, 32).unwrap();
-    let font1 = cache.get(path, 38).unwrap();
-    let fonts = (font0, font1);
-
     let items = [
         {
-            let (idle, hover) = get_sprites(renderer, "Play", fonts);
+            let (idle, hover) = get_sprites(renderer, "Play");
             MenuItem::new(
                 idle,
                 hover,
@@ -265,7 +246,7 @@ pub fn main_menu<'a>(
             )
         },
         {
-            let (idle, hover) = get_sprites(renderer, "Quit", fonts);
+            let (idle, hover) = get_sprites(renderer, "Quit");
             MenuItem::new(idle, hover, Action::Quit)
         },
     ];
@@ -1,6 +1,21 @@
 pub mod player;
 pub mod main_menu;
 pub mod background;
+pub mod enemies;
+pub mod level;
+pub mod pickups;
+pub mod hud;
+pub mod particles;
+pub mod camera;
+pub mod effects;
+pub mod raycast;
+pub mod vec2;
+pub mod handle;
+pub mod object_table;
+pub mod broadcast;
+pub mod ecs;
+
+pub use self::vec2::Vec2;
 
 use ::set::{Set, Intersects};
 
@@ -51,6 +66,15 @@ impl Bounds {
             Circle(ref c) => c.bottom(),
         }
     }
+
+    pub fn center(&self) -> Vec2 {
+        use self::Bounds::*;
+
+        match *self {
+            Rectangle(ref b) => b.center(),
+            Circle(ref c) => c.center(),
+        }
+    }
 }
 
 fn rectangle_intersects_circle(c: &Circle<f64>, r: &Rectangle<f64>) -> bool {
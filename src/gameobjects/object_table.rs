@@ -0,0 +1,103 @@
+use ::gameobjects::{Bounds, Vec2};
+use ::gameobjects::handle::Handle;
+use ::gameobjects::raycast::{Ray, all_hits, first_hit};
+
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObjectKind {
+    Player,
+    Enemy,
+    Projectile,
+    Pickup,
+    Effect,
+    Other,
+}
+
+/// What other objects can know about an object without borrowing it.
+#[derive(Clone, Copy, Debug)]
+pub struct ObjectInfo {
+    pub handle: Handle,
+    pub kind: ObjectKind,
+    pub bounds: Option<Bounds>,
+    /// Whether a level waiting for the screen to clear waits for this.
+    pub holds_up_level: bool,
+}
+
+/// A snapshot of every object in a view, taken at the start of each frame so
+/// that objects can look each other up by `Handle` while they're updating.
+#[derive(Default)]
+pub struct ObjectTable {
+    entries: Vec<ObjectInfo>,
+    lookup: HashMap<Handle, usize>,
+}
+
+impl ObjectTable {
+    pub fn new<I: IntoIterator<Item=ObjectInfo>>(infos: I) -> Self {
+        let entries = infos.into_iter().collect::<Vec<_>>();
+        let lookup = entries.iter()
+            .enumerate()
+            .map(|(i, info)| (info.handle, i))
+            .collect();
+
+        ObjectTable {
+            entries: entries,
+            lookup: lookup,
+        }
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&ObjectInfo> {
+        self.lookup.get(&handle).map(|&i| &self.entries[i])
+    }
+
+    /// Whether `handle`'s object still existed at the start of this frame.
+    pub fn is_alive(&self, handle: Handle) -> bool {
+        self.lookup.contains_key(&handle)
+    }
+
+    pub fn iter(&self) -> ::std::slice::Iter<ObjectInfo> {
+        self.entries.iter()
+    }
+
+    pub fn of_kind<'a>(
+        &'a self,
+        kind: ObjectKind
+    ) -> Box<Iterator<Item=&'a ObjectInfo> + 'a> {
+        box self.entries.iter().filter(move |info| info.kind == kind)
+    }
+
+    pub fn player(&self) -> Option<&ObjectInfo> {
+        self.of_kind(ObjectKind::Player).next()
+    }
+
+    /// The object of `kind` whose bounds are centered closest to `point`.
+    pub fn nearest(&self, point: Vec2, kind: ObjectKind) -> Option<&ObjectInfo> {
+        self.of_kind(kind)
+            .filter_map(|info|
+                info.bounds.map(|b| (info, b.center().distance(point)))
+            )
+            .fold(None, |nearest, (info, dist)| match nearest {
+                Some((_, best)) if best <= dist => nearest,
+                _ => Some((info, dist)),
+            })
+            .map(|(info, _)| info)
+    }
+
+    /// The nearest object hit by `ray` out of those of `kind`.
+    pub fn raycast(
+        &self,
+        ray: &Ray,
+        kind: ObjectKind
+    ) -> Option<(&ObjectInfo, f64)> {
+        first_hit(self.of_kind(kind), ray, |info| info.bounds)
+    }
+
+    /// Every object of `kind` hit by `ray`, nearest first.
+    pub fn raycast_all(
+        &self,
+        ray: &Ray,
+        kind: ObjectKind
+    ) -> Vec<(&ObjectInfo, f64)> {
+        all_hits(self.of_kind(kind), ray, |info| info.bounds)
+    }
+}
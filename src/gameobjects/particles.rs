@@ -0,0 +1,344 @@
+use ::gameobjects::*;
+use ::gameobjects::handle::Handle;
+use ::gameobjects::object_table::ObjectTable;
+use ::graphics::sprites::{LoadSprite, Sprite, VisibleComponent, VisibleRect};
+use ::graphics::backend::{Backend, Texture};
+use ::events::GameTime;
+use ::time::*;
+
+use std::cmp;
+use std::convert::TryInto;
+use std::f64::consts::PI;
+use sdl2::pixels::Color;
+
+/// The most particles that can be alive at once. Emitters skip whatever they
+/// can't fit in.
+const MAX_PARTICLES: usize = 2000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Look {
+    /// A rectangle in the particle's colour.
+    Rect,
+    /// The sprite at this path, stretched to the particle's size.
+    Sprite(&'static str),
+}
+
+/// How an emitter spawns particles, and how they change over their lives.
+/// Pairs are either the range a value is picked from at random, or its value
+/// at birth and at death.
+pub struct EmitterConfig {
+    /// Particles spawned per second while the emitter runs.
+    pub rate: f64,
+    /// Particles spawned as soon as the emitter starts.
+    pub burst: u32,
+    /// How long the emitter runs for, in milliseconds. Emitters without one
+    /// run until the object they're attached to is gone.
+    pub duration: Option<u32>,
+    /// In milliseconds.
+    pub lifetime: (u32, u32),
+    /// The angle particles head out at, and how far either side of it they
+    /// might stray.
+    pub direction: f64,
+    pub spread: f64,
+    pub speed: (f64, f64),
+    /// Added to every particle's velocity each second.
+    pub gravity: Vec2,
+    /// Colour and alpha at birth and at death, as RGBA.
+    pub color: ([u8; 4], [u8; 4]),
+    /// Size at birth and at death.
+    pub size: (f64, f64),
+    pub look: Look,
+}
+
+pub static EXPLOSION_SPARKS: EmitterConfig = EmitterConfig {
+    rate: 0.0,
+    burst: 40,
+    duration: Some(0),
+    lifetime: (300, 700),
+    direction: 0.0,
+    spread: PI,
+    speed: (60.0, 260.0),
+    gravity: Vec2 { x: 0.0, y: 0.0 },
+    color: ([255, 230, 120, 255], [255, 60, 20, 0]),
+    size: (5.0, 1.0),
+    look: Look::Rect,
+};
+
+pub static ENGINE_TRAIL: EmitterConfig = EmitterConfig {
+    rate: 60.0,
+    burst: 0,
+    duration: None,
+    lifetime: (200, 400),
+    direction: PI,
+    spread: 0.25,
+    speed: (80.0, 140.0),
+    gravity: Vec2 { x: 0.0, y: 0.0 },
+    color: ([120, 200, 255, 220], [40, 60, 200, 0]),
+    size: (4.0, 1.0),
+    look: Look::Rect,
+};
+
+pub static DEBRIS: EmitterConfig = EmitterConfig {
+    rate: 0.0,
+    burst: 10,
+    duration: Some(0),
+    lifetime: (600, 1200),
+    direction: 0.0,
+    spread: PI,
+    speed: (40.0, 160.0),
+    gravity: Vec2 { x: 0.0, y: 120.0 },
+    color: ([255, 255, 255, 255], [255, 255, 255, 0]),
+    size: (10.0, 4.0),
+    look: Look::Sprite("assets/debris.png"),
+};
+
+/// Asks for particles, through `GameAction::Particles`.
+#[derive(Clone, Copy)]
+pub struct Emitter {
+    pub config: &'static EmitterConfig,
+    pub position: Vec2,
+    /// Whether the emitter follows the object that asked for it around, with
+    /// `position` as an offset from that object's center.
+    pub attached: bool,
+}
+
+impl Emitter {
+    pub fn at(config: &'static EmitterConfig, position: Vec2) -> Self {
+        Emitter {
+            config: config,
+            position: position,
+            attached: false,
+        }
+    }
+
+    pub fn attached(config: &'static EmitterConfig, offset: Vec2) -> Self {
+        Emitter {
+            config: config,
+            position: offset,
+            attached: true,
+        }
+    }
+}
+
+struct RunningEmitter {
+    config: &'static EmitterConfig,
+    position: Vec2,
+    anchor: Option<(Handle, Vec2)>,
+    started_at: u32,
+    emitted: u32,
+    sprite: Option<usize>,
+    finished: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Particle {
+    config: &'static EmitterConfig,
+    sprite: Option<usize>,
+    position: Vec2,
+    velocity: Vec2,
+    born_at: u32,
+    lifetime: u32,
+}
+
+fn between(range: (f64, f64)) -> f64 {
+    use rand::random;
+
+    range.0 + (range.1 - range.0) * random::<f64>()
+}
+
+fn lerp(range: (f64, f64), t: f64) -> f64 {
+    range.0 + (range.1 - range.0) * t
+}
+
+fn lerp_color(range: ([u8; 4], [u8; 4]), t: f64) -> Color {
+    let (from, to) = range;
+    let channel = |i: usize|
+        lerp((from[i] as f64, to[i] as f64), t).round() as u8;
+
+    Color::RGBA(channel(0), channel(1), channel(2), channel(3))
+}
+
+/// Every particle in a view. Particles are plain values kept in one buffer
+/// that's reused from frame to frame, rather than being objects of their own.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    emitters: Vec<RunningEmitter>,
+    sprites: Vec<(&'static str, Sprite<Texture>)>,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        ParticleSystem {
+            particles: Vec::with_capacity(MAX_PARTICLES),
+            emitters: vec![],
+            sprites: vec![],
+        }
+    }
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    fn sprite_index(
+        &mut self,
+        renderer: &mut Backend,
+        path: &'static str
+    ) -> Option<usize> {
+        if let Some(i) = self.sprites.iter().position(|&(p, _)| p == path) {
+            return Some(i);
+        }
+
+        renderer.load_sprite(path).ok().map(|sprite| {
+            self.sprites.push((path, sprite));
+
+            self.sprites.len() - 1
+        })
+    }
+
+    /// Starts `emitter`, which was asked for by `owner`.
+    pub fn add(
+        &mut self,
+        renderer: &mut Backend,
+        emitter: Emitter,
+        owner: Handle,
+        now: u32
+    ) {
+        let sprite = match emitter.config.look {
+            Look::Rect => None,
+            Look::Sprite(path) => self.sprite_index(renderer, path),
+        };
+
+        self.emitters.push(
+            RunningEmitter {
+                config: emitter.config,
+                position: emitter.position,
+                anchor: if emitter.attached {
+                    Some((owner, emitter.position))
+                } else {
+                    None
+                },
+                started_at: now,
+                emitted: 0,
+                sprite: sprite,
+                finished: false,
+            }
+        );
+    }
+
+    fn emit(
+        emitter: &mut RunningEmitter,
+        particles: &mut Vec<Particle>,
+        objects: &ObjectTable,
+        now: u32
+    ) {
+        let config = emitter.config;
+
+        if let Some((handle, offset)) = emitter.anchor {
+            match objects.get(handle).and_then(|info| info.bounds) {
+                Some(bounds) => emitter.position = bounds.center() + offset,
+                None => {
+                    emitter.finished = true;
+
+                    return;
+                },
+            }
+        }
+
+        let age = now - emitter.started_at;
+        let running_for = config.duration.map_or(age, |d| cmp::min(age, d));
+        let due = config.burst +
+            (config.rate * running_for.exact_seconds()) as u32;
+
+        while emitter.emitted < due && particles.len() < MAX_PARTICLES {
+            let angle = config.direction + config.spread * between((-1.0, 1.0));
+            let lifetime = between(
+                (config.lifetime.0 as f64, config.lifetime.1 as f64)
+            );
+
+            particles.push(
+                Particle {
+                    config: config,
+                    sprite: emitter.sprite,
+                    position: emitter.position,
+                    velocity: Vec2::from_angle(angle) * between(config.speed),
+                    born_at: now,
+                    lifetime: lifetime as u32,
+                }
+            );
+
+            emitter.emitted += 1;
+        }
+
+        emitter.emitted = due;
+
+        if config.duration.map_or(false, |d| age >= d) {
+            emitter.finished = true;
+        }
+    }
+
+    /// Spawns new particles and moves the rest on by `time.elapsed`.
+    /// Attached emitters find what they're attached to in `objects`.
+    pub fn update(&mut self, objects: &ObjectTable, time: GameTime) {
+        let now = time.total;
+        let dt = time.elapsed.exact_seconds();
+
+        for emitter in &mut self.emitters {
+            Self::emit(emitter, &mut self.particles, objects, now);
+        }
+
+        self.emitters.retain(|e| !e.finished);
+
+        for p in &mut self.particles {
+            p.velocity += p.config.gravity * dt;
+            p.position += p.velocity * dt;
+        }
+
+        self.particles.retain(|p| now - p.born_at < p.lifetime);
+    }
+
+    pub fn sprites(&self, time: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        self.particles.iter().filter_map(|p| {
+            let t = (
+                (time.total - p.born_at) as f64 / p.lifetime as f64
+            ).min(1.0);
+            let size = lerp(p.config.size, t).max(1.0);
+
+            let color = lerp_color(p.config.color, t);
+
+            // Sprites are tinted and faded like rectangles would be
+            let visual = match p.sprite {
+                Some(i) => {
+                    let (r, g, b, a) = color.rgba();
+                    let sprite: VisibleComponent<_> =
+                        self.sprites[i].1.clone().into();
+
+                    sprite.transform(|transform| {
+                        transform.tint = Color::RGB(r, g, b);
+                        transform.alpha = a;
+                    })
+                },
+                None => VisibleRect(color).into(),
+            };
+
+            let bounds = BoundingRect {
+                width: size,
+                height: size,
+                .. Default::default()
+            }.centered_at(p.position);
+
+            bounds.try_into().ok().map(|dest| (visual, dest))
+        }).collect()
+    }
+}
@@ -0,0 +1,224 @@
+use ::gameobjects::*;
+use ::gameobjects::object_table::ObjectKind;
+use ::gameobjects::broadcast::{Broadcast, GameEvent};
+use ::gameobjects::player::{
+    GameObject,
+    GameAction,
+    GameMessage,
+    SimpleObject,
+    GunBuilder,
+    SineGun,
+    HomingLauncher,
+    Laser,
+    ChargeCannon,
+};
+use ::graphics::backend::{Backend, Texture};
+use ::graphics::sprites::{build_spritesheet, Sprite, VisibleComponent};
+use ::events::*;
+use ::view::*;
+use ::time::*;
+
+use std::convert::TryInto;
+
+const PICKUPS_PATH: &'static str = "assets/pickups.png";
+const PICKUP_SIZE: u32 = 24;
+
+/// How long a pickup stays around before it disappears.
+const PICKUP_LIFETIME: u32 = 8000;
+/// How long before disappearing a pickup starts blinking.
+const BLINK_TIME: u32 = 2000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickupKind {
+    WeaponUpgrade,
+    SineAmmo,
+    Shield,
+    ExtraLife,
+    ScoreGem,
+    Bomb,
+    /// Unlocks the gun that it builds.
+    Weapon(GunBuilder),
+}
+
+impl PickupKind {
+    /// Which frame of the pickup spritesheet this kind is drawn with.
+    fn frame(&self) -> usize {
+        match *self {
+            PickupKind::WeaponUpgrade => 0,
+            PickupKind::SineAmmo => 1,
+            PickupKind::Shield => 2,
+            PickupKind::ExtraLife => 3,
+            PickupKind::ScoreGem => 4,
+            PickupKind::Weapon(_) => 5,
+            PickupKind::Bomb => 6,
+        }
+    }
+}
+
+/// What a destroyed object might leave behind.
+pub struct DropTable {
+    /// The chance that anything is dropped at all.
+    pub chance: f64,
+    /// How likely each kind is to be chosen, relative to the others.
+    pub weights: &'static [(PickupKind, u32)],
+}
+
+pub static ASTEROID_DROPS: DropTable = DropTable {
+    chance: 0.3,
+    weights: &[
+        (PickupKind::ScoreGem, 6),
+        (PickupKind::SineAmmo, 3),
+        (PickupKind::Shield, 1),
+    ],
+};
+
+pub static ENEMY_DROPS: DropTable = DropTable {
+    chance: 0.25,
+    weights: &[
+        (PickupKind::ScoreGem, 5),
+        (PickupKind::WeaponUpgrade, 2),
+        (PickupKind::SineAmmo, 2),
+        (PickupKind::Shield, 2),
+        (PickupKind::ExtraLife, 1),
+        (PickupKind::Bomb, 1),
+        (PickupKind::Weapon(SineGun::build), 1),
+        (PickupKind::Weapon(HomingLauncher::build), 1),
+        (PickupKind::Weapon(Laser::build), 1),
+        (PickupKind::Weapon(ChargeCannon::build), 1),
+    ],
+};
+
+pub static BOSS_DROPS: DropTable = DropTable {
+    chance: 1.0,
+    weights: &[
+        (PickupKind::ExtraLife, 1),
+    ],
+};
+
+impl DropTable {
+    pub fn roll(&self) -> Option<PickupKind> {
+        use rand::random;
+
+        if random::<f64>() >= self.chance { return None; }
+
+        let total = self.weights.iter().map(|&(_, w)| w).sum::<u32>();
+
+        if total == 0 { return None; }
+
+        let mut pick = random::<u32>() % total;
+
+        for &(kind, weight) in self.weights {
+            if pick < weight { return Some(kind); }
+
+            pick -= weight;
+        }
+
+        None
+    }
+
+    /// Rolls for a drop, and spawns it centered on `center` if there is one.
+    pub fn spawn(
+        &self,
+        renderer: &mut Backend,
+        now: u32,
+        center: Vec2
+    ) -> Vec<SimpleObject> {
+        self.roll().into_iter().map(|kind|
+            box Pickup::new(renderer, now, kind, center) as SimpleObject
+        ).collect()
+    }
+}
+
+pub struct Pickup {
+    pub kind: PickupKind,
+    pub sprite: Sprite<Texture>,
+    pub bounds: BoundingRect,
+    pub velocity: Vec2,
+    pub expires_at: u32,
+}
+
+impl Pickup {
+    pub fn new(
+        renderer: &mut Backend,
+        now: u32,
+        kind: PickupKind,
+        center: Vec2
+    ) -> Self {
+        Pickup {
+            kind: kind,
+            sprite: build_spritesheet(
+                renderer.load_image(PICKUPS_PATH.as_ref()).unwrap(),
+                PICKUP_SIZE,
+                PICKUP_SIZE
+            ).remove(kind.frame()),
+            bounds: BoundingRect {
+                width: PICKUP_SIZE as _,
+                height: PICKUP_SIZE as _,
+                .. Default::default()
+            }.centered_at(center),
+            velocity: Vec2::new(-60.0, 0.0),
+            expires_at: now + PICKUP_LIFETIME,
+        }
+    }
+}
+
+impl GameObject<Keys, Texture> for Pickup {
+    fn update(
+        &mut self,
+        _: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        self.bounds.translate(self.velocity * time.elapsed.exact_seconds());
+
+        if time.total >= self.expires_at {
+            vec![GameAction::Delete]
+        } else {
+            vec![]
+        }
+    }
+
+    fn sprites(&self, time: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        let blinking =
+            self.expires_at.saturating_sub(time.total) < BLINK_TIME &&
+            (time.total / 100) % 2 == 0;
+
+        if blinking { return vec![]; }
+
+        vec![
+            (self.sprite.clone().into(), self.bounds.try_into().unwrap()),
+        ]
+    }
+
+    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Pickup }
+
+    fn receive_message<'a>(
+        &'a mut self,
+        _: &mut Context<Keys>,
+        _: GameTime,
+        msg: GameMessage<'a, Keys, Texture>
+    ) -> Vec<GameAction<Keys, Texture>> {
+        match msg {
+            GameMessage::Hit { other, .. }
+                if other.kind() == ObjectKind::Player =>
+                    vec![
+                        GameAction::Broadcast(
+                            vec![
+                                Broadcast::to_subscribers(
+                                    GameEvent::PickupCollected
+                                ),
+                            ]
+                        ),
+                        GameAction::Delete,
+                    ],
+            _ => vec![],
+        }
+    }
+
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        Some(GameMessage::Collect(self.kind))
+    }
+}
@@ -0,0 +1,191 @@
+use ::gameobjects::*;
+use ::gameobjects::object_table::ObjectKind;
+use ::gameobjects::broadcast::{Broadcast, GameEvent};
+use ::gameobjects::effects::ScreenEffect;
+use ::events::*;
+use ::view::*;
+use ::graphics::sprites::{VisibleComponent, LoadSprite, Sprite};
+use ::graphics::backend::{Backend, Texture};
+use ::time::*;
+
+use super::*;
+
+use std::convert::TryInto;
+use sdl2::pixels::Color;
+
+const SHIELD_OVERLAY_PATH: &'static str = "assets/shield.png";
+const SHIELD_DURATION: u32 = 3000;
+/// How long after the shield goes down before it can be used again.
+const SHIELD_COOLDOWN: u32 = 10000;
+/// How long before going down the shield starts blinking.
+const SHIELD_BLINK_TIME: u32 = 600;
+
+pub const BOMB_STOCK: u32 = 3;
+pub const MAX_BOMBS: u32 = 5;
+const BOMB_DAMAGE: u32 = 150;
+
+/// A bubble around the ship that soaks up every hit while it's up, and then
+/// has to recharge.
+pub struct TimedShield {
+    pub active_until: u32,
+    pub ready_at: u32,
+    pub overlay: Sprite<Texture>,
+}
+
+impl TimedShield {
+    pub fn new(renderer: &mut Backend) -> Self {
+        TimedShield {
+            active_until: 0,
+            ready_at: 0,
+            overlay: renderer.load_sprite(SHIELD_OVERLAY_PATH).unwrap(),
+        }
+    }
+
+    pub fn is_active(&self, now: u32) -> bool {
+        now < self.active_until
+    }
+
+    /// Puts the shield up, if it's recharged. Returns whether it did.
+    pub fn activate(&mut self, now: u32) -> bool {
+        if now < self.ready_at { return false; }
+
+        self.active_until = now + SHIELD_DURATION;
+        self.ready_at = self.active_until + SHIELD_COOLDOWN;
+
+        true
+    }
+
+    /// From 0 to 1, how much time the shield has left while it's up, or how
+    /// far it's recharged while it's down.
+    pub fn gauge(&self, now: u32) -> f64 {
+        if self.is_active(now) {
+            (self.active_until - now) as f64 / SHIELD_DURATION as f64
+        } else {
+            let left = self.ready_at.saturating_sub(now);
+
+            1.0 - left as f64 / SHIELD_COOLDOWN as f64
+        }
+    }
+
+    pub fn sprites(&self, ship: BoundingRect, now: u32)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        if !self.is_active(now) { return vec![]; }
+
+        let blinking = self.active_until - now < SHIELD_BLINK_TIME &&
+            (now / 100) % 2 == 0;
+
+        if blinking { return vec![]; }
+
+        let size = ship.width.max(ship.height) * 1.4;
+
+        vec![
+            (
+                self.overlay.clone().into(),
+                BoundingRect {
+                    width: size,
+                    height: size,
+                    .. Default::default()
+                }.centered_at(ship.center()).try_into().unwrap(),
+            )
+        ]
+    }
+}
+
+/// The player's secondary abilities, used alongside whichever gun is
+/// selected.
+pub struct Secondary {
+    pub shield: TimedShield,
+    pub bombs: u32,
+}
+
+impl Secondary {
+    pub fn new(renderer: &mut Backend) -> Self {
+        Secondary {
+            shield: TimedShield::new(renderer),
+            bombs: BOMB_STOCK,
+        }
+    }
+
+    /// Uses up a bomb to clear `screen`, or does nothing if there are none
+    /// left.
+    pub fn drop_bomb(
+        &mut self,
+        screen: BoundingRect
+    ) -> Vec<GameAction<Keys, Texture>> {
+        if self.bombs == 0 { return vec![]; }
+
+        self.bombs -= 1;
+
+        vec![
+            GameAction::AddObjects(vec![box BombBlast { bounds: screen }]),
+            GameAction::Broadcast(
+                vec![Broadcast::to_all(GameEvent::BombDetonated)]
+            ),
+            GameAction::ScreenEffects(
+                vec![
+                    ScreenEffect::Shake(0.5),
+                    ScreenEffect::Flash(Color::RGBA(255, 255, 255, 200), 400),
+                ]
+            ),
+        ]
+    }
+}
+
+/// Covers the whole screen for a single frame, hitting every enemy on it.
+/// Enemy bullets are cancelled separately, by `GameEvent::BombDetonated`.
+pub struct BombBlast {
+    pub bounds: BoundingRect,
+}
+
+impl GameObject<Keys, Texture> for BombBlast {
+    fn update(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let centers = context.objects.of_kind(ObjectKind::Enemy)
+            .filter_map(|enemy| enemy.bounds)
+            .map(|b| b.center())
+            .collect::<Vec<_>>();
+
+        let explosions = centers.into_iter().map(|center|
+            box Explosion::with_bounds(
+                &mut context.renderer,
+                time.total,
+                BoundingRect {
+                    width: 64.0,
+                    height: 64.0,
+                    .. Default::default()
+                }.centered_at(center)
+            ) as SimpleObject
+        ).collect();
+
+        vec![
+            GameAction::AddObjects(explosions),
+            GameAction::Delete,
+        ]
+    }
+
+    fn sprites(&self, _: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        vec![]
+    }
+
+    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Effect }
+
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        Some(
+            GameMessage::Hit {
+                other: self as _,
+                info: DamageInfo {
+                    damage: BOMB_DAMAGE,
+                    filter: DamageFilter::Player,
+                },
+            }
+        )
+    }
+}
@@ -1,11 +1,34 @@
 use ::gameobjects::*;
-use ::gameobjects::main_menu::PauseMenuBuilder;
+use ::gameobjects::main_menu::{PauseMenuBuilder, MainMenuBuilder};
+use ::gameobjects::level::{Level, LevelDirector, Checkpoint};
+use ::gameobjects::pickups::{PickupKind, ASTEROID_DROPS};
 use ::gameobjects::background::*;
+use ::gameobjects::raycast::{Ray, first_hit};
+use ::gameobjects::handle::{Handle, HandleAllocator};
+use ::gameobjects::object_table::{ObjectTable, ObjectInfo, ObjectKind};
+use ::gameobjects::broadcast::{Broadcast, GameEvent, Topic};
+use ::gameobjects::ecs::{EntitySpec, World};
+use ::gameobjects::hud::{Hud, PlayerStatus};
+use ::gameobjects::particles::{
+    ParticleSystem,
+    Emitter,
+    ENGINE_TRAIL,
+    EXPLOSION_SPARKS,
+    DEBRIS,
+};
+use ::gameobjects::effects::{ScreenEffects, ScreenEffect};
+use ::gameobjects::camera::{self, Camera, CameraMode};
 use ::events::*;
 use ::view::*;
+use ::graphics::backend::{Backend, Texture};
+use ::graphics::display::LOGICAL_SIZE;
+use ::graphics::layers::{DrawList, DrawKey, Layer};
+use ::graphics::alpha_mask::{AlphaMask, shared_spritesheet_masks};
 use ::graphics::sprites::{
     build_spritesheet,
     AnimatedSprite,
+    Animation,
+    AnimationSet,
     VisibleComponent,
     LoadSprite,
     Sprite,
@@ -15,19 +38,25 @@ use ::graphics::sprites::{
 use ::time::*;
 use ::set::Intersects;
 
+use std::rc::Rc;
 use std::convert::TryInto;
 use sdl2::pixels::Color;
-use sdl2::render::{Texture, Renderer};
-use sdl2_image::LoadTexture;
 
 mod ship;
+mod weapons;
+mod abilities;
 pub mod command_builder;
 
 use self::ship::*;
+use self::abilities::Secondary;
+
+pub use self::ship::{Gun, GunBuilder, GunStatus, SineGun, StandardGun};
+pub use self::weapons::{HomingLauncher, Laser, ChargeCannon};
 
 const ASTEROID_PATH: &'static str = "assets/asteroid.png";
 const EXPLOSION_PATH: &'static str = "assets/explosion.png";
 const SHIP_PATH: &'static str = "assets/spaceship.png";
+const LEVEL_PATH: &'static str = "assets/levels/level1.lvl";
 const BACKGROUND_PATHS: [&'static str; 3] = [
     "assets/spaceBG.png",
     "assets/spaceMG.png",
@@ -37,23 +66,32 @@ const BACKGROUND_PATHS: [&'static str; 3] = [
 type Background = ParallaxSet<
     u32,
     Texture,
-    [([f64; 2], [f64; 2], Sprite<Texture>); 3]
+    [(Vec2, Vec2, Sprite<Texture>); 3]
 >;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DamageFilter {
     Player,
     Enemy,
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct DamageInfo {
-    filter: DamageFilter,
-    damage: u32,
+    pub filter: DamageFilter,
+    pub damage: u32,
 }
 
 pub enum GameAction<K: KeySet, T: GetSize> {
     Delete,
     AddObjects(Vec<Box<GameObject<K, T>>>),
-    // Broadcast(Vec<Broadcast>),
+    /// Adds entities to the view's `World`, for things simple enough to be
+    /// made of components.
+    SpawnEntities(Vec<EntitySpec>),
+    Broadcast(Vec<Broadcast>),
+    /// Starts particle emitters. Attached emitters follow the object that
+    /// started them.
+    Particles(Vec<Emitter>),
+    ScreenEffects(Vec<ScreenEffect>),
 }
 
 pub enum GameMessage<'a, K: KeySet + 'a, T: GetSize + 'a> {
@@ -61,7 +99,12 @@ pub enum GameMessage<'a, K: KeySet + 'a, T: GetSize + 'a> {
         other: &'a (GameObject<K, T> + 'a),
         info: DamageInfo,
     },
-    __Other,
+    Event {
+        sender: Option<Handle>,
+        event: GameEvent,
+    },
+    /// Sent by a pickup to whatever touches it.
+    Collect(PickupKind),
 }
 
 pub trait GameObject<K: KeySet, T: GetSize> {
@@ -70,9 +113,18 @@ pub trait GameObject<K: KeySet, T: GetSize> {
         context: &mut Context<K>,
         time: GameTime
     ) -> Vec<GameAction<K, T>>;
+    /// What to draw, in world coordinates. The view's camera decides where
+    /// on the screen that ends up.
     fn sprites(&self, time: GameTime) -> Vec<(VisibleComponent<Texture>, Dest)>;
     fn bounds(&self) -> Option<Bounds> { None }
 
+    /// Objects that want pixel-perfect collisions return the mask of their
+    /// current frame and where it's drawn. This is only consulted once
+    /// `bounds` already intersect.
+    fn collision_mask(&self, _: GameTime) -> Option<(&AlphaMask, Dest)> {
+        None
+    }
+
     fn receive_message<'a>(
         &'a mut self,
         _: &mut Context<K>,
@@ -85,31 +137,92 @@ pub trait GameObject<K: KeySet, T: GetSize> {
     fn on_hit(&self) -> Option<GameMessage<K, T>> {
         None
     }
+
+    /// Called once when the object is added to a view, with the handle that
+    /// other objects will know it by.
+    fn on_spawn(&mut self, _: Handle) {}
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Other }
+
+    /// Whether a level that's waiting for the screen to clear has to wait
+    /// for this object to go. Every enemy does unless it says otherwise.
+    fn holds_up_level(&self) -> bool { self.kind() == ObjectKind::Enemy }
+
+    /// Where the object is drawn among the others, with higher depths in
+    /// front. Goes by the object's kind unless overridden.
+    fn depth(&self) -> i32 {
+        match self.kind() {
+            ObjectKind::Other => 0,
+            ObjectKind::Pickup => 10,
+            ObjectKind::Enemy => 20,
+            ObjectKind::Player => 30,
+            ObjectKind::Projectile => 40,
+            ObjectKind::Effect => 50,
+        }
+    }
+
+    /// Whether the object wants to receive broadcasts sent to `topic`.
+    fn is_subscribed(&self, _: Topic) -> bool { false }
+
+    /// What the HUD should show, for the object the player controls.
+    fn player_status(&self, _: GameTime) -> Option<PlayerStatus> {
+        None
+    }
+
+    /// How much of its health a boss has left, as a fraction, for the HUD
+    /// to show.
+    fn boss_health(&self) -> Option<f64> { None }
 }
 
 pub type SimpleObject = Box<GameObject<Keys, Texture>>;
 
+/// The `Dest` covering the box around `bounds`, if it has any area.
+pub fn bounds_dest(bounds: Bounds) -> Option<Dest> {
+    BoundingRect {
+        x: bounds.left(),
+        y: bounds.top(),
+        width: bounds.right() - bounds.left(),
+        height: bounds.bottom() - bounds.top(),
+    }.try_into().ok()
+}
+
+/// The narrow phase of collision detection, run after the `Bounds` of `a`
+/// and `b` are known to intersect. Objects without a mask are treated as
+/// solid over the whole of their bounds.
+fn masks_overlap(a: &SimpleObject, b: &SimpleObject, time: GameTime) -> bool {
+    match (a.collision_mask(time), b.collision_mask(time)) {
+        (Some((mask_a, dest_a)), Some((mask_b, dest_b))) =>
+            mask_a.overlaps(dest_a, mask_b, dest_b),
+        (Some((mask, dest)), None) =>
+            b.bounds().and_then(bounds_dest)
+                .map_or(true, |rect| mask.overlaps_rect(dest, rect)),
+        (None, Some((mask, dest))) =>
+            a.bounds().and_then(bounds_dest)
+                .map_or(true, |rect| mask.overlaps_rect(dest, rect)),
+        (None, None) => true,
+    }
+}
+
 pub struct Explosion {
     pub sprite: AnimatedSprite<u32, Texture>,
     pub bounds: BoundingRect,
 }
 
 impl Explosion {
-    pub fn new(renderer: &mut Renderer, now: u32, [x, y]: [f64; 2]) -> Self {
+    pub fn new(renderer: &mut Backend, now: u32, position: Vec2) -> Self {
         Self::with_bounds(
             renderer,
             now,
             BoundingRect {
-                x: x,
-                y: y,
                 width: 96.0,
                 height: 96.0,
-            }
+                .. Default::default()
+            }.at(position)
         )
     }
 
     pub fn with_bounds(
-        renderer: &mut Renderer,
+        renderer: &mut Backend,
         now: u32,
         bounds: BoundingRect
     ) -> Self {
@@ -119,7 +232,7 @@ impl Explosion {
                 40.0,
                 {
                     let mut sprites = build_spritesheet(
-                        renderer.load_texture(
+                        renderer.load_image(
                             EXPLOSION_PATH.as_ref()
                         ).unwrap(),
                         96,
@@ -149,6 +262,8 @@ impl GameObject<Keys, Texture> for Explosion {
         }
     }
 
+    fn kind(&self) -> ObjectKind { ObjectKind::Effect }
+
     fn sprites(&self, time: GameTime)
         -> Vec<(VisibleComponent<Texture>, Dest)>
     {
@@ -161,33 +276,95 @@ impl GameObject<Keys, Texture> for Explosion {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AsteroidSize {
+    Large,
+    Medium,
+    Small,
+}
+
+impl AsteroidSize {
+    /// The size of the asteroid relative to the spritesheet.
+    pub fn scale(&self) -> f64 {
+        match *self {
+            AsteroidSize::Large => 1.0,
+            AsteroidSize::Medium => 0.6,
+            AsteroidSize::Small => 0.35,
+        }
+    }
+
+    pub fn hp(&self) -> u32 {
+        match *self {
+            AsteroidSize::Large => 100,
+            AsteroidSize::Medium => 50,
+            AsteroidSize::Small => 20,
+        }
+    }
+
+    /// What an asteroid of this size breaks into, and how many of them.
+    pub fn fragments(&self) -> Option<(AsteroidSize, u32)> {
+        match *self {
+            AsteroidSize::Large => Some((AsteroidSize::Medium, 3)),
+            AsteroidSize::Medium => Some((AsteroidSize::Small, 3)),
+            AsteroidSize::Small => None,
+        }
+    }
+}
+
 pub struct Asteroid {
     pub sprite: AnimatedSprite<u32, Texture>,
+    /// One per frame of the whole spritesheet, shared between asteroids.
+    pub masks: Rc<Vec<AlphaMask>>,
+    pub size: AsteroidSize,
     pub hp: u32,
     pub bounds: BoundingRect,
-    pub velocity: [f64; 2],
+    pub velocity: Vec2,
+    /// How far the asteroid turns each second, in degrees, on top of tumbling
+    /// through its frames. The masks don't turn with it, but asteroids are
+    /// round enough that it doesn't show.
+    pub turn_rate: f64,
+    pub spawned: u32,
 }
 
 impl GameObject<Keys, Texture> for Asteroid {
     fn update(
         &mut self,
-        _: &mut Context<Keys>,
+        context: &mut Context<Keys>,
         time: GameTime,
     ) -> Vec<GameAction<Keys, Texture>> {
         let elapsed = time.elapsed.exact_seconds();
 
-        self.bounds.x += self.velocity[0] * elapsed;
-        self.bounds.y += self.velocity[1] * elapsed;
+        self.bounds.translate(self.velocity * elapsed);
 
-        vec![]
+        let play_area = context.play_area;
+
+        // Asteroids come in from just off the play area, so they're only gone
+        // once they're further away than that
+        let margin = self.bounds.width;
+        let area = BoundingRect {
+            x: play_area.x - margin,
+            y: play_area.y - margin,
+            width: play_area.width + 2.0 * margin,
+            height: play_area.height + 2.0 * margin,
+        };
+
+        if self.bounds.intersects(&area) {
+            vec![]
+        } else {
+            vec![GameAction::Delete]
+        }
     }
 
     fn sprites(&self, time: GameTime)
         -> Vec<(VisibleComponent<Texture>, Dest)>
     {
+        let frame: VisibleComponent<_> =
+            self.sprite.frame(time.total).clone().into();
+        let age = time.total.saturating_sub(self.spawned).exact_seconds();
+
         vec![
             (
-                self.sprite.frame(time.total).clone().into(),
+                frame.rotated(self.turn_rate * age),
                 self.bounds.try_into().unwrap(),
             )
         ]
@@ -199,18 +376,37 @@ impl GameObject<Keys, Texture> for Asteroid {
         time: GameTime,
         msg: GameMessage<'a, Keys, Texture>
     ) -> Vec<GameAction<Keys, Texture>> {
+        // Hits after the killing one in the same tick are ignored, so that
+        // the asteroid only breaks apart once
+        if self.hp == 0 { return vec![]; }
+
         if let GameMessage::Hit { info: DamageInfo { damage, .. }, .. } = msg {
-            if damage > self.hp {
+            if damage >= self.hp {
+                self.hp = 0;
+
+                let mut remains = self.break_apart(&mut ctx.renderer, time);
+
+                remains.push(
+                    box Explosion::with_bounds(
+                        &mut ctx.renderer,
+                        time.total,
+                        self.bounds
+                    )
+                );
+
+                let center = self.bounds.center();
+
                 vec![
-                    GameAction::AddObjects(
+                    GameAction::AddObjects(remains),
+                    GameAction::Particles(
                         vec![
-                            box Explosion::new(
-                                &mut ctx.renderer,
-                                time.total,
-                                [self.bounds.x, self.bounds.y]
-                            ),
+                            Emitter::at(&DEBRIS, center),
+                            Emitter::at(&EXPLOSION_SPARKS, center),
                         ]
                     ),
+                    GameAction::ScreenEffects(
+                        vec![ScreenEffect::Shake(0.2 * self.size.scale())]
+                    ),
                     GameAction::Delete,
                 ]
             } else {
@@ -236,60 +432,156 @@ impl GameObject<Keys, Texture> for Asteroid {
     }
 
     fn bounds(&self) -> Option<Bounds> {
-        let w = self.bounds.width;
+        let Vec2 { x, y } = self.bounds.center();
 
         Some(
             Circle {
-                x: self.bounds.x + w / 2.0,
-                y: self.bounds.y + w / 2.0,
-                radius: 40.0,
+                x: x,
+                y: y,
+                radius: 40.0 * self.size.scale(),
             }.into()
         )
     }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Enemy }
+
+    /// Asteroids keep drifting in for as long as they're turned on, so a
+    /// level waiting for them all to go could be stuck forever.
+    fn holds_up_level(&self) -> bool { false }
+
+    fn collision_mask(&self, time: GameTime) -> Option<(&AlphaMask, Dest)> {
+        Some(
+            (
+                &self.masks[self.sprite.frame_index(time.total)],
+                self.bounds.try_into().unwrap(),
+            )
+        )
+    }
 }
 
 impl Asteroid {
+    /// A large asteroid drifting in from the right at a random speed.
     pub fn new(
-        renderer: &mut Renderer,
+        renderer: &mut Backend,
         now: u32,
-        [x, y]: [f64; 2]
+        position: Vec2
+    ) -> Asteroid {
+        use rand::random;
+
+        Self::with_motion(
+            renderer,
+            now,
+            AsteroidSize::Large,
+            position,
+            Vec2::new(
+                -40.0 - 40.0 * random::<f64>(),
+                30.0 * (random::<f64>() - 0.5)
+            ),
+            Self::random_spin()
+        )
+    }
+
+    /// How many frames of the spritesheet to turn through per second, where
+    /// negative spins go backwards.
+    fn random_spin() -> f64 {
+        use rand::random;
+
+        let speed = 15.0 + 30.0 * random::<f64>();
+
+        if random() { speed } else { -speed }
+    }
+
+    pub fn with_motion(
+        renderer: &mut Backend,
+        now: u32,
+        size: AsteroidSize,
+        position: Vec2,
+        velocity: Vec2,
+        spin: f64
     ) -> Asteroid {
         let [w, h] = [96; 2];
+        let scale = size.scale();
 
-        Asteroid {
-            sprite: AnimatedSprite::from_spritesheet(
-                now,
-                30.0,
-                {
-                    let mut sprites = build_spritesheet(
-                        renderer.load_texture(
-                            ASTEROID_PATH.as_ref()
-                        ).unwrap(),
-                        w,
-                        h
-                    );
+        let mut sprites = build_spritesheet(
+            renderer.load_image(ASTEROID_PATH.as_ref()).unwrap(),
+            w,
+            h
+        );
+        let masks = shared_spritesheet_masks(ASTEROID_PATH, w, h).unwrap();
 
-                    for _ in 0..4 { sprites.pop(); }
+        for _ in 0..4 { sprites.pop(); }
 
-                    sprites
-                }
+        // Spinning backwards plays the frames in reverse, rather than
+        // reversing the sprites, so that frame indices still line up with
+        // the shared masks
+        let frames = if spin < 0.0 {
+            (0..sprites.len()).rev().collect()
+        } else {
+            (0..sprites.len()).collect()
+        };
+        let animation = Animation {
+            fps: spin.abs(),
+            frames: frames,
+        };
+
+        Asteroid {
+            sprite: AnimatedSprite::new(
+                sprites,
+                AnimationSet::from_tuples(vec![((), animation)]),
+                (),
+                now
             ),
-            hp: 100,
+            masks: masks,
+            size: size,
+            hp: size.hp(),
             bounds: BoundingRect {
-                x: x,
-                y: y,
-                width: w as _,
-                height: h as _,
-            },
-            velocity: [-50.0, 0.0],
+                width: w as f64 * scale,
+                height: h as f64 * scale,
+                .. Default::default()
+            }.at(position),
+            velocity: velocity,
+            turn_rate: 2.0 * spin,
+            spawned: now,
         }
     }
-}
 
-#[derive(Eq, PartialEq, Clone, Copy)]
-pub enum BulletKind {
-    Standard,
-    Sine,
+    /// The fragments that the asteroid leaves behind when it's destroyed,
+    /// flung out in random directions. Only the smallest fragments can drop
+    /// pickups, so that one large asteroid doesn't drop a pile of them.
+    fn break_apart(
+        &self,
+        renderer: &mut Backend,
+        time: GameTime
+    ) -> Vec<SimpleObject> {
+        use rand::random;
+        use std::f64::consts::PI;
+
+        let center = self.bounds.center();
+
+        let (size, count) = if let Some(fragments) = self.size.fragments() {
+            fragments
+        } else {
+            return ASTEROID_DROPS.spawn(renderer, time.total, center);
+        };
+
+        let fragment_size = 96.0 * size.scale();
+        let offset = random::<f64>() * 2.0 * PI;
+
+        (0..count).map(|i| {
+            let angle = offset + i as f64 * 2.0 * PI / count as f64;
+            let direction = Vec2::from_angle(angle);
+
+            box Asteroid::with_motion(
+                renderer,
+                time.total,
+                size,
+                center + direction * fragment_size / 2.0 -
+                    Vec2::new(fragment_size, fragment_size) / 2.0,
+                self.velocity + direction * (40.0 + 80.0 * random::<f64>()),
+                Self::random_spin()
+            ) as SimpleObject
+        }).collect()
+    }
 }
 
 pub struct ShipViewBuilder;
@@ -303,65 +595,93 @@ impl ViewBuilder<Keys, VisibleComponent<Texture>> for ShipViewBuilder {
     }
 }
 
+/// Starts the level again from a checkpoint, after a game over.
+pub struct CheckpointBuilder(pub Checkpoint);
+
+#[allow(boxed_local)]
+impl ViewBuilder<Keys, VisibleComponent<Texture>> for CheckpointBuilder {
+    fn build_view(self: Box<Self>, context: &mut Context<Keys>)
+        -> Box<View<Keys, VisibleComponent<Texture>>>
+    {
+        Box::new(
+            Some(
+                ShipView::with_director(
+                    context.renderer,
+                    LevelDirector::from_checkpoint(
+                        Level::load(LEVEL_PATH).unwrap(),
+                        self.0
+                    )
+                )
+            )
+        )
+    }
+}
+
 pub struct ShipView {
-    objects: Vec<SimpleObject>,
+    objects: Vec<(Handle, SimpleObject)>,
+    handles: HandleAllocator,
+    broadcasts: Vec<Broadcast>,
+    /// Entities that have been moved over from being `GameObject`s.
+    pub world: World,
     background: Background,
-    last_asteroid_time: u32,
+    director: LevelDirector,
+    hud: Hud,
+    particles: ParticleSystem,
+    effects: ScreenEffects,
+    camera: Camera,
     total_time: u32,
 }
 
 impl ShipView {
-    pub fn new(renderer: &mut Renderer) -> Self {
-        let (_, screen_h) = renderer.output_size().unwrap();
-
-        ShipView {
-            last_asteroid_time: 0,
-            objects: vec![
-                box Ship {
-                    gun: ShipGun {
-                        kind: BulletKind::Standard,
-                        standard: StandardGun::new(0),
-                        sine: SineGun::new(0),
-                    },
-                    bounds: BoundingRect {
-                        width: 50.0,
-                        height: 50.0,
-                        x: 0.0,
-                        y: (screen_h / 2) as f64 - 25.0,
-                    },
-                    dir: Default::default(),
-                    sprites: ALL_FRAMES.into_iter()
-                        .cloned()
-                        .zip(
-                            build_spritesheet(
-                                renderer.load_texture(
-                                    SHIP_PATH.as_ref()
-                                ).unwrap(),
-                                43,
-                                39
-                            ).into_iter()
-                        ).collect(),
-                }
-            ],
+    pub fn new(renderer: &mut Backend) -> Self {
+        Self::with_director(
+            renderer,
+            LevelDirector::new(Level::load(LEVEL_PATH).unwrap())
+        )
+    }
+
+    pub fn with_director(
+        renderer: &mut Backend,
+        director: LevelDirector
+    ) -> Self {
+        let (screen_w, screen_h) = LOGICAL_SIZE;
+        let play_area = camera::play_area();
+
+        let mut view = ShipView {
+            director: director,
+            hud: Hud::new(),
+            particles: ParticleSystem::new(),
+            effects: ScreenEffects::new(),
+            camera: Camera {
+                limits: Some(play_area),
+                .. Camera::looking_at(
+                    play_area,
+                    Vec2::new(screen_w as _, screen_h as _)
+                )
+            },
+            objects: vec![],
+            handles: HandleAllocator::new(),
+            broadcasts: vec![],
+            world: World::new(),
             background: Background::new(
                 [
                     (
-                        [-20.0, 0.0],
-                        [0.0, 0.0],
+                        Vec2::new(-20.0, 0.0),
+                        Vec2::new(0.0, 0.0),
                         renderer.load_sprite(
                             BACKGROUND_PATHS[0]
                         ).unwrap()
                     ),
                     (
-                        [-40.0, 0.0],
-                        [0.0, 0.0],
+                        Vec2::new(-40.0, 0.0),
+                        Vec2::new(0.0, 0.0),
                         renderer.load_sprite(
                             BACKGROUND_PATHS[1]
                         ).unwrap()
                     ),
                     (
-                        [-80.0, 0.0],
-                        [0.0, 0.0],
+                        Vec2::new(-80.0, 0.0),
+                        Vec2::new(0.0, 0.0),
                         renderer.load_sprite(
                             BACKGROUND_PATHS[2]
                         ).unwrap()
@@ -370,7 +690,115 @@ impl ShipView {
                 0
             ),
             total_time: 0,
-        }
+        };
+
+        let ship = view.spawn(
+            box Ship {
+                gun: WeaponInventory::new(
+                    vec![StandardGun::build(0), SineGun::build(0)]
+                ),
+                secondary: Secondary::new(renderer),
+                bounds: BoundingRect {
+                    width: 50.0,
+                    height: 50.0,
+                    x: play_area.left(),
+                    y: play_area.center().y - 25.0,
+                },
+                dir: Default::default(),
+                sprites: ALL_FRAMES.into_iter()
+                    .cloned()
+                    .zip(
+                        build_spritesheet(
+                            renderer.load_image(
+                                SHIP_PATH.as_ref()
+                            ).unwrap(),
+                            43,
+                            39
+                        ).into_iter()
+                    ).collect(),
+                hp: PLAYER_MAX_HP,
+                lives: PLAYER_LIVES,
+                invulnerable_until: 0,
+                shield: 0,
+                score: 0,
+            }
+        );
+
+        view.particles.add(
+            renderer,
+            Emitter::attached(&ENGINE_TRAIL, Vec2::new(-22.0, 0.0)),
+            ship,
+            0
+        );
+
+        view.camera.mode = CameraMode::Follow {
+            target: ship,
+            stiffness: 4.0,
+        };
+
+        view
+    }
+
+    /// Adds `object` to the view, returning the handle it can be found by.
+    pub fn spawn(&mut self, mut object: SimpleObject) -> Handle {
+        let handle = self.handles.allocate();
+
+        object.on_spawn(handle);
+        self.objects.push((handle, object));
+
+        handle
+    }
+
+    /// The object that `handle` refers to, if it's still alive. This is a
+    /// linear search, since objects move around every tick, so anything that
+    /// looks up lots of handles in one go should build an `ObjectTable`
+    /// instead, which keeps a map from handles to its entries.
+    pub fn get(&self, handle: Handle) -> Option<&SimpleObject> {
+        self.objects.iter()
+            .find(|&&(h, _)| h == handle)
+            .map(|&(_, ref obj)| obj)
+    }
+
+    /// Like `get`, and just as slow.
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut SimpleObject> {
+        self.objects.iter_mut()
+            .find(|&&mut (h, _)| h == handle)
+            .map(|&mut (_, ref mut obj)| obj)
+    }
+
+    pub fn is_alive(&self, handle: Handle) -> bool {
+        self.handles.is_alive(handle)
+    }
+
+    fn object_table(&self) -> ObjectTable {
+        ObjectTable::new(
+            self.objects.iter().map(|&(handle, ref obj)|
+                ObjectInfo {
+                    handle: handle,
+                    kind: obj.kind(),
+                    bounds: obj.bounds(),
+                    holds_up_level: obj.holds_up_level(),
+                }
+            )
+        )
+    }
+}
+
+impl ShipView {
+    /// The nearest object hit by `ray` out of those that pass `filter`, and
+    /// how far along the ray it was hit.
+    pub fn raycast<F: FnMut(&SimpleObject) -> bool>(
+        &self,
+        ray: &Ray,
+        mut filter: F
+    ) -> Option<(&SimpleObject, f64)> {
+        first_hit(
+            self.objects.iter()
+                .map(|&(_, ref o)| o)
+                .filter(|o| filter(o)),
+            ray,
+            |o| o.bounds()
+        )
     }
 }
 
@@ -409,28 +837,11 @@ impl View<Keys, VisibleComponent<Texture>> for ShipView {
         use ::split_iterator::*;
         use ::coalesce::*;
         use std::mem;
-        use rand::random;
-
-        self.total_time += elapsed;
-
-        let (screen_w, screen_h) = context.renderer.output_size().unwrap();
 
-        let asteroid_interval = 1000;
+        // Real time keeps going during a hit-stop, but the game's doesn't
+        let elapsed = self.effects.update(elapsed);
 
-        if self.total_time - self.last_asteroid_time > asteroid_interval {
-            self.objects.push(
-                box Asteroid::new(
-                    context.renderer,
-                    self.total_time,
-                    [
-                        screen_w as _,
-                        (random::<u32>() % (screen_h - 96)) as _
-                    ]
-                ) as _
-            );
-
-            self.last_asteroid_time = self.total_time;
-        }
+        self.total_time += elapsed;
 
         let game_time = GameTime {
             elapsed: elapsed,
@@ -441,17 +852,67 @@ impl View<Keys, VisibleComponent<Texture>> for ShipView {
             return Action::Quit;
         }
 
-        context.renderer.set_draw_color(Color::RGB(0, 0, 0));
-        context.renderer.clear();
+        context.renderer.clear_to(Color::RGB(0, 0, 0));
 
-        let (screen_w, screen_h) = context.renderer.output_size().unwrap();
+        let screen = context.screen;
 
-        let screen = Dest::default().with_size(screen_w, screen_h);
+        context.objects = self.object_table();
 
-        let messages = {
-            let mut message_container = self.objects.iter_mut().map(
-                |m| { let update = m.update(context, game_time); (m, update) }
-            ).collect::<Vec<_>>();
+        let spawned = self.director.update(
+            context.renderer,
+            &context.objects,
+            context.play_area,
+            game_time
+        );
+
+        for obj in spawned {
+            self.spawn(obj);
+        }
+
+        self.particles.update(&context.objects, game_time);
+
+        self.camera.viewport = Vec2::new(screen.width as _, screen.height as _);
+        self.camera.update(&context.objects, game_time);
+
+        let broadcasts = mem::replace(&mut self.broadcasts, vec![]);
+
+        for b in &broadcasts {
+            self.world.receive(&b.event);
+        }
+
+        // Everything hears about last tick's broadcasts before anything
+        // updates, so that the order objects are in doesn't matter
+        let delivered = self.objects.iter_mut().map(
+            |&mut (handle, ref mut m)| {
+                let mut actions = vec![];
+
+                for b in &broadcasts {
+                    if b.is_for(handle, |topic| m.is_subscribed(topic)) {
+                        actions.extend(
+                            m.receive_message(
+                                context,
+                                game_time,
+                                GameMessage::Event {
+                                    sender: b.sender,
+                                    event: b.event.clone(),
+                                }
+                            )
+                        );
+                    }
+                }
+
+                actions
+            }
+        ).collect::<Vec<_>>();
+
+        let mut messages = {
+            let mut message_container = self.objects.iter_mut()
+                .zip(delivered)
+                .map(|(&mut (_, ref mut m), mut actions)| {
+                    actions.extend(m.update(context, game_time));
+
+                    (m, actions)
+                }).collect::<Vec<_>>();
 
             message_container.split_iter_mut(
                 |&mut (ref mut head, ref mut msgs), tail| {
@@ -464,7 +925,8 @@ impl View<Keys, VisibleComponent<Texture>> for ShipView {
                                     .map_or(
                                         false,
                                         |(a, b)| a.intersects(&b)
-                                    )
+                                    ) &&
+                                    masks_overlap(&**head, &**other, game_time)
                                 {
                                     if let Some(msg) = head.on_hit() {
                                         other_msgs.extend(
@@ -494,42 +956,155 @@ impl View<Keys, VisibleComponent<Texture>> for ShipView {
             ).collect::<Vec<_>>()
         };
 
-        self.objects = mem::replace(&mut self.objects, vec![]).into_iter()
-            .zip(messages)
-            .flat_map(
-                |(obj, obj_msgs)| {
-                    use self::GameAction::*;
-
-                    let (delete, objs): (Vec<_>, Vec<_>) = obj_msgs.into_iter()
-                        .map(
-                            |m| match m {
-                                Delete => (true, vec![]),
-                                AddObjects(objs) => (false, objs),
-                                // _ => Default::default(),
-                            }
-                        ).unzip();
-
-                    let maybe_obj = if delete.into_iter().any(|b| b) {
-                        None
-                    } else {
-                        Some(obj)
-                    };
-
-                    objs.into_iter().flat_map(
-                        |o| o
-                    ).chain(maybe_obj.into_iter())
+        let remains = self.world.update(
+            context,
+            game_time,
+            context.play_area,
+            &mut self.objects,
+            &mut messages
+        );
+
+        let mut next_objects = Vec::with_capacity(self.objects.len());
+        // New objects go after all the old ones, so that objects are always
+        // kept in the order they were spawned in
+        let mut added = vec![];
+        let mut game_over = false;
+
+        for ((handle, obj), obj_msgs) in
+            mem::replace(&mut self.objects, vec![]).into_iter().zip(messages)
+        {
+            use self::GameAction::*;
+
+            let mut delete = false;
+
+            for m in obj_msgs {
+                match m {
+                    Delete => delete = true,
+                    AddObjects(objs) => for mut o in objs {
+                        let h = self.handles.allocate();
+
+                        o.on_spawn(h);
+                        added.push((h, o));
+                    },
+                    SpawnEntities(specs) => for spec in specs {
+                        self.world.spawn(spec);
+                    },
+                    GameAction::Broadcast(bs) => for mut b in bs {
+                        match b.event {
+                            GameEvent::PlayerDied { lives_left: 0 } =>
+                                game_over = true,
+                            GameEvent::BossPhaseChanged { .. } =>
+                                self.background.pause(self.total_time),
+                            GameEvent::BossDefeated =>
+                                self.background.resume(self.total_time),
+                            _ => {},
+                        }
+
+                        b.sender = Some(handle);
+                        self.broadcasts.push(b);
+                    },
+                    GameAction::ScreenEffects(effects) => for e in effects {
+                        self.effects.apply(e);
+                    },
+                    Particles(emitters) => for e in emitters {
+                        self.particles.add(
+                            context.renderer,
+                            e,
+                            handle,
+                            self.total_time
+                        );
+                    },
                 }
-            ).collect();
-
-        Action::Render(
-            box self.background.get_destinations(
-                screen,
-                self.total_time
-            ).into_iter().map(|(s, d)| (s.into(), d)).chain(
-                self.objects.iter().flat_map(
-                    move |a| a.sprites(game_time).into_iter()
-                )
-            )
-        )
+            }
+
+            if delete {
+                self.handles.release(handle);
+            } else {
+                next_objects.push((handle, obj));
+            }
+        }
+
+        next_objects.extend(added);
+
+        self.objects = next_objects;
+
+        for remain in remains {
+            self.spawn(remain);
+        }
+
+        if game_over {
+            return match self.director.checkpoint() {
+                Some(checkpoint) =>
+                    Action::ChangeView(box CheckpointBuilder(checkpoint)),
+                None => Action::ChangeView(box MainMenuBuilder),
+            };
+        }
+
+        if self.director.is_finished() {
+            return Action::ChangeView(box MainMenuBuilder);
+        }
+
+        let status = self.objects.iter()
+            .filter_map(|&(_, ref obj)| obj.player_status(game_time))
+            .next();
+        let boss_health = self.objects.iter()
+            .filter_map(|&(_, ref obj)| obj.boss_health())
+            .next();
+
+        self.hud.update(
+            context.renderer,
+            context.screen,
+            status,
+            boss_health
+        );
+
+        let (shake_x, shake_y) = self.effects.shake_offset();
+        let camera = self.camera;
+        let shaken = move |(s, d): (VisibleComponent<Texture>, Dest)|
+            (
+                s,
+                Dest {
+                    x: d.x + shake_x,
+                    y: d.y + shake_y,
+                    .. d
+                },
+            );
+
+        // Objects are drawn in world coordinates, so they go through the
+        // camera, while the background is already drawn to fit the screen
+        let placed = |(s, d): (VisibleComponent<Texture>, Dest)|
+            shaken((s, camera.project(d)));
+
+        let mut draws = DrawList::new();
+
+        draws.extend(
+            Layer::Background,
+            self.background.get_destinations(screen, self.total_time)
+                .into_iter()
+                .map(|(s, d)| shaken((s.into(), d)))
+        );
+
+        for &(_, ref obj) in &self.objects {
+            draws.extend(
+                DrawKey::new(Layer::World, obj.depth()),
+                obj.sprites(game_time).into_iter().map(&placed)
+            );
+        }
+
+        draws.extend(
+            Layer::World,
+            self.world.sprites().into_iter().map(&placed)
+        );
+        draws.extend(
+            Layer::Effects,
+            self.particles.sprites(game_time).into_iter().map(&placed)
+        );
+
+        // The HUD stays put while the rest of the screen shakes, so that it
+        // can still be read
+        draws.extend(Layer::Hud, self.hud.sprites());
+        draws.extend(Layer::Overlay, self.effects.flash(screen));
+
+        Action::Render(box draws.into_sorted().into_iter())
     }
 }
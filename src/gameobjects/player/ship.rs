@@ -1,4 +1,11 @@
 use ::gameobjects::*;
+use ::gameobjects::object_table::{ObjectTable, ObjectKind};
+use ::gameobjects::broadcast::{Broadcast, GameEvent};
+use ::gameobjects::pickups::PickupKind;
+use ::gameobjects::hud::PlayerStatus;
+use ::gameobjects::particles::{Emitter, EXPLOSION_SPARKS};
+use ::gameobjects::effects::ScreenEffect;
+use ::gameobjects::ecs::{Collider, EntitySpec};
 use ::events::*;
 use ::view::*;
 use ::graphics::sprites::{
@@ -6,16 +13,18 @@ use ::graphics::sprites::{
     VisibleRect,
     Sprite,
 };
+use ::graphics::backend::Texture;
 use ::time::*;
-use ::set::Intersects;
 
 use super::*;
 use super::command_builder::CommandBuilder;
+use super::abilities::{Secondary, MAX_BOMBS};
 
+use std::cmp;
 use std::convert::TryInto;
+use std::f64::consts::PI;
 use std::collections::HashMap;
 use sdl2::pixels::Color;
-use sdl2::render::Texture;
 
 /// The different states our ship might be in. In the image, they're ordered
 /// from left to right, then from top to bottom.
@@ -44,44 +53,157 @@ pub static ALL_FRAMES: [ShipFrame; 9] = [
     ShipFrame::DownSlow,
 ];
 
-pub struct GunArgs {
+pub struct GunArgs<'a> {
     pub bounds: Bounds,
+    /// Everything else in the view, for guns that care what's in the way.
+    pub objects: &'a ObjectTable,
+    pub play_area: BoundingRect,
 }
 
-pub trait Gun: Sized {
+/// Guns fire by handing back actions for the ship to take, so that they can
+/// spawn their bullets as objects or as entities, whichever suits them.
+pub trait Gun {
     fn spawn_bullets(
         &mut self,
         args: GunArgs,
         _: GameTime
-    ) -> Vec<SimpleObject>;
+    ) -> Vec<GameAction<Keys, Texture>>;
+    /// Called when the trigger is let go, for guns that fire on release.
+    fn release(
+        &mut self,
+        _: GunArgs,
+        _: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        vec![]
+    }
+    /// What the gun is called. A `WeaponInventory` never holds two guns with
+    /// the same name.
+    fn name(&self) -> &'static str;
     fn next_weapon(&mut self) {}
+    fn prev_weapon(&mut self) {}
+    /// Switches to the weapon in slot `index`, counting from zero, if there
+    /// is one.
+    fn select_weapon(&mut self, _: usize) {}
+    /// Gives the gun another weapon, for guns that hold more than one.
+    fn unlock(&mut self, _: Box<Gun>) {}
+    /// Makes the gun more powerful, for guns that can be upgraded.
+    fn upgrade(&mut self) {}
+    /// Undoes an `upgrade`, when the player dies.
+    fn downgrade(&mut self) {}
+    /// Tops up the gun's ammo, for guns that use any.
+    fn refill(&mut self) {}
+    /// Tops up only the gun called `name`, if this is it or holds it.
+    fn refill_named(&mut self, name: &str) {
+        if self.name() == name {
+            self.refill();
+        }
+    }
+    fn status(&self) -> GunStatus;
+}
+
+/// Builds a gun, given the time it's built at. Weapon pickups carry one of
+/// these, so that any gun can be unlocked.
+pub type GunBuilder = fn(u32) -> Box<Gun>;
+
+/// What the HUD shows about a gun. Levels count from zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GunStatus {
+    pub name: &'static str,
+    pub level: usize,
+    pub max_level: usize,
+    pub details: String,
 }
 
-pub struct ShipGun {
-    pub kind: BulletKind,
-    pub sine: SineGun,
-    pub standard: StandardGun,
+/// Every weapon the player has unlocked, in the order they were unlocked.
+/// Only the selected weapon fires, and only it is upgraded or downgraded.
+pub struct WeaponInventory {
+    pub weapons: Vec<Box<Gun>>,
+    pub selected: usize,
 }
 
-impl Gun for ShipGun {
+impl WeaponInventory {
+    /// Holds `weapons`, with the first of them selected. There has to be at
+    /// least one.
+    pub fn new(weapons: Vec<Box<Gun>>) -> Self {
+        WeaponInventory {
+            weapons: weapons,
+            selected: 0,
+        }
+    }
+}
+
+impl Gun for WeaponInventory {
     fn spawn_bullets(
         &mut self,
         args: GunArgs,
         time: GameTime
-    ) -> Vec<SimpleObject> {
-        if self.kind == BulletKind::Sine {
-            self.sine.spawn_bullets(args, time)
-        } else {
-            self.standard.spawn_bullets(args, time)
-        }
+    ) -> Vec<GameAction<Keys, Texture>> {
+        self.weapons[self.selected].spawn_bullets(args, time)
+    }
+
+    fn release(
+        &mut self,
+        args: GunArgs,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        self.weapons[self.selected].release(args, time)
+    }
+
+    fn name(&self) -> &'static str {
+        self.weapons[self.selected].name()
     }
 
     fn next_weapon(&mut self) {
-        self.kind = if self.kind == BulletKind::Sine {
-            BulletKind::Standard
+        self.selected = (self.selected + 1) % self.weapons.len();
+    }
+
+    fn prev_weapon(&mut self) {
+        let count = self.weapons.len();
+
+        self.selected = (self.selected + count - 1) % count;
+    }
+
+    fn select_weapon(&mut self, index: usize) {
+        if index < self.weapons.len() {
+            self.selected = index;
+        }
+    }
+
+    /// Adds `gun` and switches to it. Unlocking a weapon that's already held
+    /// refills that weapon instead.
+    fn unlock(&mut self, gun: Box<Gun>) {
+        let name = gun.name();
+
+        if let Some(i) = self.weapons.iter().position(|w| w.name() == name) {
+            self.weapons[i].refill();
         } else {
-            BulletKind::Sine
-        };
+            self.weapons.push(gun);
+            self.selected = self.weapons.len() - 1;
+        }
+    }
+
+    fn upgrade(&mut self) {
+        self.weapons[self.selected].upgrade();
+    }
+
+    fn downgrade(&mut self) {
+        self.weapons[self.selected].downgrade();
+    }
+
+    fn refill(&mut self) {
+        for weapon in &mut self.weapons {
+            weapon.refill();
+        }
+    }
+
+    fn refill_named(&mut self, name: &str) {
+        for weapon in &mut self.weapons {
+            weapon.refill_named(name);
+        }
+    }
+
+    fn status(&self) -> GunStatus {
+        self.weapons[self.selected].status()
     }
 }
 
@@ -108,10 +230,26 @@ pub struct Ship<G: Gun/*, C: CommandBuilder<Self, ShipCommand>*/> {
     pub bounds: BoundingRect,
     //pub command_builder: C,
     pub gun: G,
+    pub secondary: Secondary,
     pub dir: [Direction; 2],
     pub sprites: HashMap<ShipFrame, Sprite<Texture>>,
+    pub hp: u32,
+    pub lives: u32,
+    /// The ship can't be damaged until this time, so that it isn't killed
+    /// again straight after respawning.
+    pub invulnerable_until: u32,
+    /// Soaks up damage before `hp` does.
+    pub shield: u32,
+    pub score: u32,
 }
 
+pub const PLAYER_MAX_HP: u32 = 100;
+pub const PLAYER_LIVES: u32 = 3;
+pub const PLAYER_MAX_LIVES: u32 = 9;
+pub const PLAYER_MAX_SHIELD: u32 = 60;
+const RESPAWN_INVULNERABILITY: u32 = 2000;
+const SCORE_GEM_VALUE: u32 = 100;
+
 impl<G: Gun> Ship<G> {
     fn get_control(up: bool, down: bool, left: bool, right: bool) -> [Direction; 2] {
         use ::fixed_size_iter::FixedSizeIntoMap;
@@ -126,23 +264,26 @@ impl<G: Gun> Ship<G> {
         )
     }
 
-    fn movement_direction(dirs: [Direction; 2]) -> [f64; 2] {
+    fn movement_direction(dirs: [Direction; 2]) -> Vec2 {
         use self::Direction::*;
         use ::fixed_size_iter::FixedSizeIntoMap;
 
-        Self::normalize(
+        Vec2::from(
             dirs.map(|d| match d { Neg => -1.0, Zero => 0.0, Pos => 1.0 })
-        )
+        ).normalize()
     }
 
-    fn normalize([x, y]: [f64; 2]) -> [f64; 2] {
-        match [x, y] {
-            [0.0, 0.0] => [0.0, 0.0],
-            [x, y] => {
-                let len = (x*x + y*y).sqrt();
-
-                [x / len, y / len]
-            },
+    fn collect(&mut self, kind: PickupKind, now: u32) {
+        match kind {
+            PickupKind::WeaponUpgrade => self.gun.upgrade(),
+            PickupKind::SineAmmo => self.gun.refill_named(SINE_NAME),
+            PickupKind::Shield => self.shield = PLAYER_MAX_SHIELD,
+            PickupKind::ExtraLife =>
+                self.lives = cmp::min(self.lives + 1, PLAYER_MAX_LIVES),
+            PickupKind::ScoreGem => self.score += SCORE_GEM_VALUE,
+            PickupKind::Bomb => self.secondary.bombs =
+                cmp::min(self.secondary.bombs + 1, MAX_BOMBS),
+            PickupKind::Weapon(build) => self.gun.unlock(build(now)),
         }
     }
 
@@ -172,16 +313,10 @@ impl<G: Gun> GameObject<Keys, Texture> for Ship<G> {
         context: &mut Context<Keys>,
         time: GameTime
     ) -> Vec<GameAction<Keys, Texture>> {
-        use ::fixed_size_iter::FixedSizeIntoMap;
-
         let player_speed = 230.0;
 
         let dt = time.elapsed.exact_seconds();
 
-        let (sw, sh) = context.renderer.output_size().map(
-            |(a, b)| (a as f64, b as f64)
-        ).unwrap();
-
         self.dir = {
             let keys = &context.events.down;
 
@@ -193,74 +328,322 @@ impl<G: Gun> GameObject<Keys, Texture> for Ship<G> {
             )
         };
 
-        let [dx, dy] = Self::movement_direction(self.dir).map(
-            |a| a * dt * player_speed
+        self.bounds.translate(
+            Self::movement_direction(self.dir) * dt * player_speed
         );
 
-        self.bounds.x += dx;
-        self.bounds.y += dy;
+        let screen = context.play_area;
 
-        self.bounds = self.bounds.move_inside(
-            &BoundingRect {
-                width: sw,
-                height: sh,
-                .. Default::default()
+        self.bounds = self.bounds.move_inside(&screen).unwrap();
+
+        {
+            let pressed = &context.events.pressed;
+            let slots = [
+                pressed.weapon_1,
+                pressed.weapon_2,
+                pressed.weapon_3,
+                pressed.weapon_4,
+                pressed.weapon_5,
+            ];
+
+            if pressed.next_weapon {
+                self.gun.next_weapon();
+            }
+
+            if pressed.prev_weapon {
+                self.gun.prev_weapon();
+            }
+
+            if let Some(slot) = slots.iter().position(|&p| p) {
+                self.gun.select_weapon(slot);
             }
-        ).unwrap();
+        }
+
+        let mut actions = vec![];
+
+        if context.events.pressed.shield {
+            self.secondary.shield.activate(time.total);
+        }
 
-        if context.events.pressed.next_weapon {
-            self.gun.next_weapon();
+        if context.events.pressed.bomb {
+            actions.extend(self.secondary.drop_bomb(screen));
         }
 
+        let bounds: Bounds = self.bounds.into();
+        let objects = &context.objects;
+        let args = || GunArgs {
+            bounds: bounds,
+            objects: objects,
+            play_area: screen,
+        };
+
         if context.events.down.fire {
-            vec![
-                GameAction::AddObjects(
-                    self.gun.spawn_bullets(
-                        GunArgs { bounds: self.bounds.into(), },
-                        time
-                    )
-                )
-            ]
-        } else {
-            vec![]
+            actions.extend(self.gun.spawn_bullets(args(), time));
         }
+
+        if context.events.released.fire {
+            actions.extend(self.gun.release(args(), time));
+        }
+
+        actions
     }
 
-    fn sprites(&self, _: GameTime)
+    fn sprites(&self, time: GameTime)
         -> Vec<(VisibleComponent<Texture>, Dest)>
     {
+        let blinking = time.total < self.invulnerable_until &&
+            (time.total / 100) % 2 == 0;
+
+        let mut out = if blinking {
+            vec![]
+        } else {
+            vec![
+                (
+                    self.sprites[&Self::get_frame(self.dir)].clone().into(),
+                    self.bounds.try_into().unwrap(),
+                )
+            ]
+        };
+
+        out.extend(self.secondary.shield.sprites(self.bounds, time.total));
+
+        out
+    }
+
+    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Player }
+
+    fn player_status(&self, time: GameTime) -> Option<PlayerStatus> {
+        let now = time.total;
+
+        Some(
+            PlayerStatus {
+                hp: self.hp,
+                max_hp: PLAYER_MAX_HP,
+                shield: self.shield,
+                max_shield: PLAYER_MAX_SHIELD,
+                lives: self.lives,
+                score: self.score,
+                bombs: self.secondary.bombs,
+                timed_shield: self.secondary.shield.gauge(now),
+                timed_shield_active: self.secondary.shield.is_active(now),
+                gun: self.gun.status(),
+            }
+        )
+    }
+
+    /// The ship doesn't hurt what it touches, but this lets it know that it
+    /// was the player that touched it.
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        Some(
+            GameMessage::Hit {
+                other: self as _,
+                info: DamageInfo {
+                    damage: 0,
+                    filter: DamageFilter::Player,
+                },
+            }
+        )
+    }
+
+    fn receive_message<'a>(
+        &'a mut self,
+        ctx: &mut Context<Keys>,
+        time: GameTime,
+        m: GameMessage<'a, Keys, Texture>
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let damage = match m {
+            GameMessage::Hit {
+                info: DamageInfo { filter: DamageFilter::Enemy, damage },
+                ..
+            } => damage,
+            GameMessage::Collect(kind) => {
+                self.collect(kind, time.total);
+
+                return vec![];
+            },
+            _ => 0,
+        };
+
+        if
+            damage == 0 ||
+            time.total < self.invulnerable_until ||
+            self.secondary.shield.is_active(time.total)
+        {
+            return vec![];
+        }
+
+        let absorbed = cmp::min(damage, self.shield);
+
+        self.shield -= absorbed;
+
+        let damage = damage - absorbed;
+
+        if damage == 0 { return vec![]; }
+
+        if damage < self.hp {
+            self.hp -= damage;
+
+            return vec![
+                GameAction::ScreenEffects(
+                    vec![
+                        ScreenEffect::Shake(0.3),
+                        ScreenEffect::HitStop(40),
+                    ]
+                ),
+            ];
+        }
+
+        self.lives = self.lives.saturating_sub(1);
+        self.hp = PLAYER_MAX_HP;
+        self.gun.downgrade();
+        self.invulnerable_until = time.total + RESPAWN_INVULNERABILITY;
+
         vec![
-            (
-                self.sprites[&Self::get_frame(self.dir)].clone().into(),
-                self.bounds.try_into().unwrap(),
-            )
+            GameAction::AddObjects(
+                vec![
+                    box Explosion::with_bounds(
+                        &mut ctx.renderer,
+                        time.total,
+                        self.bounds
+                    )
+                ]
+            ),
+            GameAction::Particles(
+                vec![Emitter::at(&EXPLOSION_SPARKS, self.bounds.center())]
+            ),
+            GameAction::ScreenEffects(
+                vec![
+                    ScreenEffect::Shake(0.7),
+                    ScreenEffect::HitStop(150),
+                    ScreenEffect::Flash(Color::RGBA(255, 60, 60, 140), 300),
+                ]
+            ),
+            GameAction::Broadcast(
+                vec![
+                    Broadcast::to_all(
+                        GameEvent::PlayerDied { lives_left: self.lives }
+                    )
+                ]
+            ),
         ]
     }
+}
 
-    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
+/// Where a cannon sits relative to the top left of the ship, and the angle
+/// it fires at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cannon {
+    pub offset: [f64; 2],
+    pub angle: f64,
+}
+
+pub struct StandardLevel {
+    pub fire_interval: u32,
+    pub cannons: &'static [Cannon],
+}
+
+pub static STANDARD_LEVELS: [StandardLevel; 4] = [
+    StandardLevel {
+        fire_interval: 400,
+        cannons: &[
+            Cannon { offset: [30.0, 6.0], angle: 0.0 },
+            Cannon { offset: [30.0, 40.0], angle: 0.0 },
+        ],
+    },
+    StandardLevel {
+        fire_interval: 300,
+        cannons: &[
+            Cannon { offset: [30.0, 6.0], angle: 0.0 },
+            Cannon { offset: [30.0, 40.0], angle: 0.0 },
+        ],
+    },
+    StandardLevel {
+        fire_interval: 250,
+        cannons: &[
+            Cannon { offset: [30.0, 6.0], angle: -0.08 },
+            Cannon { offset: [40.0, 23.0], angle: 0.0 },
+            Cannon { offset: [30.0, 40.0], angle: 0.08 },
+        ],
+    },
+    StandardLevel {
+        fire_interval: 180,
+        cannons: &[
+            Cannon { offset: [20.0, 2.0], angle: -0.2 },
+            Cannon { offset: [30.0, 6.0], angle: -0.08 },
+            Cannon { offset: [40.0, 23.0], angle: 0.0 },
+            Cannon { offset: [30.0, 40.0], angle: 0.08 },
+            Cannon { offset: [20.0, 44.0], angle: 0.2 },
+        ],
+    },
+];
+
+pub struct SineLevel {
+    pub fire_interval: u32,
+    pub amplitude: f64,
+    pub max_ammo: u8,
+    /// Each pair fires one bullet weaving up and one weaving down, with
+    /// every pair after the first weaving half as far as the one before.
+    pub pairs: u32,
 }
 
+const SINE_NAME: &'static str = "Sine";
+
+pub static SINE_LEVELS: [SineLevel; 4] = [
+    SineLevel {
+        fire_interval: 80,
+        amplitude: 90.0,
+        max_ammo: 10,
+        pairs: 1,
+    },
+    SineLevel {
+        fire_interval: 70,
+        amplitude: 110.0,
+        max_ammo: 14,
+        pairs: 1,
+    },
+    SineLevel {
+        fire_interval: 60,
+        amplitude: 130.0,
+        max_ammo: 18,
+        pairs: 2,
+    },
+    SineLevel {
+        fire_interval: 50,
+        amplitude: 150.0,
+        max_ammo: 24,
+        pairs: 2,
+    },
+];
+
 pub struct SineGun {
     pub last_ammo_at: u32,
     pub last_shot_at: u32,
     pub ammo_intervals: [u32; 3],
     pub ammo: u8,
-    pub max_ammo: u8,
+    pub level: usize,
 }
 
 impl SineGun {
     pub fn new(now: u32) -> SineGun {
-        let max = 10;
-
         SineGun {
             last_ammo_at: now,
             last_shot_at: 0,
             ammo_intervals: [1000, 700, 400],
-            ammo: max,
-            max_ammo: max,
+            ammo: SINE_LEVELS[0].max_ammo,
+            level: 0,
         }
     }
 
+    pub fn build(now: u32) -> Box<Gun> {
+        box SineGun::new(now)
+    }
+
+    pub fn stats(&self) -> &'static SineLevel {
+        &SINE_LEVELS[self.level]
+    }
+
     fn get_interval(&self) -> u32 {
         use ::gameobjects::MinMax;
 
@@ -275,9 +658,11 @@ impl Gun for SineGun {
         &mut self,
         args: GunArgs,
         time: GameTime
-    ) -> Vec<SimpleObject> {
+    ) -> Vec<GameAction<Keys, Texture>> {
         use ::gameobjects::MinMax;
 
+        let stats = self.stats();
+
         {
             let mut time_diff = time.total - self.last_ammo_at;
             let mut interval = self.get_interval();
@@ -287,7 +672,8 @@ impl Gun for SineGun {
 
                 time_diff -= interval;
 
-                self.ammo = (self.ammo as u32 + 1).min(self.max_ammo as _) as _;
+                self.ammo = (self.ammo as u32 + 1)
+                    .min(stats.max_ammo as _) as _;
 
                 interval = self.get_interval();
             }
@@ -296,7 +682,7 @@ impl Gun for SineGun {
         {
             let time_diff = time.total - self.last_shot_at;
 
-            if time_diff >= 80 {
+            if time_diff >= stats.fire_interval {
                 self.last_shot_at = time.total;
             } else {
                 return vec![];
@@ -315,31 +701,83 @@ impl Gun for SineGun {
         let cannon1_y = args.bounds.top() + 6.0;
         let cannon2_y = args.bounds.bottom() - 10.0;
 
-        vec![
-            box SineBullet::new(
-                [cannons_x, cannon1_y],
-                -90.0,
-                time.total
-            ),
-            box SineBullet::new(
-                [cannons_x, cannon2_y],
-                90.0,
-                time.total
+        let bullets = (0..stats.pairs).flat_map(|pair| {
+            let amplitude = stats.amplitude / 2f64.powi(pair as _);
+
+            vec![
+                box SineBullet::new(
+                    Vec2::new(cannons_x, cannon1_y),
+                    -amplitude,
+                    time.total
+                ) as SimpleObject,
+                box SineBullet::new(
+                    Vec2::new(cannons_x, cannon2_y),
+                    amplitude,
+                    time.total
+                ) as SimpleObject,
+            ]
+        }).collect();
+
+        vec![GameAction::AddObjects(bullets)]
+    }
+
+    fn name(&self) -> &'static str { SINE_NAME }
+
+    fn refill(&mut self) {
+        self.ammo = self.stats().max_ammo;
+    }
+
+    fn upgrade(&mut self) {
+        if self.level + 1 < SINE_LEVELS.len() {
+            self.level += 1;
+        }
+    }
+
+    fn downgrade(&mut self) {
+        use ::gameobjects::MinMax;
+
+        self.level = self.level.saturating_sub(1);
+        self.ammo = self.ammo.min(self.stats().max_ammo);
+    }
+
+    fn status(&self) -> GunStatus {
+        let stats = self.stats();
+
+        GunStatus {
+            name: self.name(),
+            level: self.level,
+            max_level: SINE_LEVELS.len() - 1,
+            details: format!(
+                "ammo {}/{}, {} bullets, amplitude {}",
+                self.ammo,
+                stats.max_ammo,
+                stats.pairs * 2,
+                stats.amplitude
             ),
-        ]
+        }
     }
 }
 
 pub struct StandardGun {
     pub last_shot_at: u32,
+    pub level: usize,
 }
 
 impl StandardGun {
     pub fn new(now: u32) -> StandardGun {
         StandardGun {
             last_shot_at: now,
+            level: 0,
         }
     }
+
+    pub fn build(now: u32) -> Box<Gun> {
+        box StandardGun::new(now)
+    }
+
+    pub fn stats(&self) -> &'static StandardLevel {
+        &STANDARD_LEVELS[self.level]
+    }
 }
 
 impl Gun for StandardGun {
@@ -347,51 +785,91 @@ impl Gun for StandardGun {
         &mut self,
         args: GunArgs,
         time: GameTime
-    ) -> Vec<SimpleObject> {
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let stats = self.stats();
         let time_diff = time.total - self.last_shot_at;
 
-        if time_diff < 400 {
+        if time_diff < stats.fire_interval {
             return vec![];
         }
 
         self.last_shot_at = time.total;
 
-        let cannons_x = args.bounds.left() + 30.0;
-        let cannon1_y = args.bounds.top() + 6.0;
-        let cannon2_y = args.bounds.bottom() - 10.0;
+        let origin = Vec2::new(args.bounds.left(), args.bounds.top());
 
         vec![
-            box Bullet::new(
-                [cannons_x, cannon1_y],
-            ),
-            box Bullet::new(
-                [cannons_x, cannon2_y],
+            GameAction::SpawnEntities(
+                stats.cannons.iter().map(|cannon|
+                    bullet(origin + Vec2::from(cannon.offset), cannon.angle)
+                ).collect()
             ),
         ]
     }
+
+    fn name(&self) -> &'static str { "Standard" }
+
+    fn upgrade(&mut self) {
+        if self.level + 1 < STANDARD_LEVELS.len() {
+            self.level += 1;
+        }
+    }
+
+    fn downgrade(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    fn status(&self) -> GunStatus {
+        let stats = self.stats();
+
+        GunStatus {
+            name: self.name(),
+            level: self.level,
+            max_level: STANDARD_LEVELS.len() - 1,
+            details: format!(
+                "{} cannons, {:.1} shots/s",
+                stats.cannons.len(),
+                1000.0 / stats.fire_interval as f64
+            ),
+        }
+    }
+}
+
+/// Whether a player bullet should be destroyed by `m`. Bullets pass through
+/// friendly fire and other projectiles.
+pub fn bullet_stopped_by(m: &GameMessage<Keys, Texture>) -> bool {
+    match *m {
+        GameMessage::Hit {
+            info: DamageInfo { filter: DamageFilter::Player, .. },
+            ..
+        } => false,
+        GameMessage::Hit { other, .. } =>
+            other.kind() != ObjectKind::Projectile,
+        _ => false,
+    }
 }
 
 pub struct SineBullet {
     pub bounds: BoundingRect,
     pub born_at: u32,
     pub angular_velocity: f64,
-    pub origin_y: f64,
+    pub origin: Vec2,
+    pub velocity: Vec2,
     pub amplitude: f64,
 }
 
 impl SineBullet {
-    pub fn new([x, y]: [f64; 2], amplitude: f64, now: u32) -> SineBullet {
+    pub fn new(position: Vec2, amplitude: f64, now: u32) -> SineBullet {
         SineBullet {
             amplitude: amplitude,
             born_at: now,
             angular_velocity: 4.0,
             bounds: BoundingRect {
-                x: x,
-                y: y,
                 width: 8.0,
                 height: 4.0,
-            },
-            origin_y: y,
+                .. Default::default()
+            }.at(position),
+            origin: position,
+            velocity: Vec2::new(270.0, 0.0),
         }
     }
 }
@@ -402,23 +880,18 @@ impl GameObject<Keys, Texture> for SineBullet {
         context: &mut Context<Keys>,
         time: GameTime
     ) -> Vec<GameAction<Keys, Texture>> {
-        let velocity_x = 270.0;
+        let alive_secs = (time.total - self.born_at).exact_seconds();
 
-        let time_alive = time.total - self.born_at;
+        // The bullet oscillates perpendicular to its direction of travel
+        let side = self.velocity.normalize().rotate(PI / 2.0);
 
-        let (elapsed, alive_secs) = (
-            time.elapsed.exact_seconds(),
-            time_alive.exact_seconds(),
+        self.bounds = self.bounds.at(
+            self.origin +
+            self.velocity * alive_secs +
+            side * self.amplitude * (self.angular_velocity * alive_secs).sin()
         );
 
-        self.bounds.x += velocity_x * elapsed;
-        self.bounds.y = self.origin_y + self.amplitude * (
-            self.angular_velocity * alive_secs
-        ).sin();
-
-        let screen = context.renderer.output_size().map(
-            |(w, h)| BoundingRect::default().with_size(w as _, h as _)
-        ).unwrap();
+        let screen = context.play_area;
 
         if
             self.bounds.left() > screen.left() &&
@@ -443,6 +916,8 @@ impl GameObject<Keys, Texture> for SineBullet {
 
     fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
 
+    fn kind(&self) -> ObjectKind { ObjectKind::Projectile }
+
     fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
         Some(
             GameMessage::Hit {
@@ -461,9 +936,7 @@ impl GameObject<Keys, Texture> for SineBullet {
         time: GameTime,
         m: GameMessage<'a, Keys, Texture>
     ) -> Vec<GameAction<Keys, Texture>> {
-        if let GameMessage::Hit {
-            info: DamageInfo { filter: DamageFilter::Player, .. }, ..
-        } = m {
+        if !bullet_stopped_by(&m) {
             vec![]
         } else {
             vec![
@@ -487,101 +960,27 @@ impl GameObject<Keys, Texture> for SineBullet {
     }
 }
 
-pub struct Bullet {
-    pub bounds: BoundingRect,
-    pub velocity: [f64; 2],
-}
-
-impl Bullet {
-    pub fn new([x, y]: [f64; 2]) -> Bullet {
-        Bullet {
-            bounds: BoundingRect {
-                x: x,
-                y: y,
-                width: 8.0,
-                height: 4.0,
-            },
-            velocity: [1800.0, 0.0],
-        }
-    }
-}
-
-impl GameObject<Keys, Texture> for Bullet {
-    fn update(
-        &mut self,
-        context: &mut Context<Keys>,
-        time: GameTime
-    ) -> Vec<GameAction<Keys, Texture>> {
-        let elapsed = time.elapsed.exact_seconds();
-
-        self.bounds.x += self.velocity[0] * elapsed;
-        self.bounds.y += self.velocity[1] * elapsed;
-
-        let screen = context.renderer.output_size().map(
-            |(w, h)| BoundingRect::default().with_size(w as _, h as _)
-        ).unwrap();
-
-        if self.bounds.intersects(&screen) {
-            vec![]
-        } else {
-            vec![GameAction::Delete]
-        }
-    }
-
-    fn sprites(&self, _: GameTime)
-        -> Vec<(VisibleComponent<Texture>, Dest)>
-    {
-        vec![
-            (
-                VisibleRect(Color::RGB(230, 230, 30)).into(),
-                self.bounds.try_into().unwrap(),
-            )
-        ]
-    }
-
-    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
-
-    fn receive_message<'a>(
-        &'a mut self,
-        ctx: &mut Context<Keys>,
-        time: GameTime,
-        m: GameMessage<'a, Keys, Texture>
-    ) -> Vec<GameAction<Keys, Texture>> {
-        if let GameMessage::Hit {
-            info: DamageInfo { filter: DamageFilter::Player, .. },
-            ..
-        } = m {
-            vec![]
-        } else {
-            vec![
-                GameAction::Delete,
-                GameAction::AddObjects(
-                    vec![
-                        box Explosion::with_bounds(
-                            &mut ctx.renderer,
-                            time.total,
-                            BoundingRect {
-                                x: self.bounds.x,
-                                y: self.bounds.y,
-                                width: 10.0,
-                                height: 10.0,
-                            }
-                        )
-                    ]
-                ),
-            ]
-        }
-    }
-
-    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
-        Some(
-            GameMessage::Hit {
-                other: self as _,
-                info: DamageInfo {
-                    damage: 20,
-                    filter: DamageFilter::Player,
-                },
+/// A bullet from the standard gun. There are lots of these and all they do
+/// is fly straight, so they're entities rather than objects.
+pub fn bullet(position: Vec2, angle: f64) -> EntitySpec {
+    let size = Vec2::new(8.0, 4.0);
+
+    EntitySpec::at(position)
+        .velocity(Vec2::from_angle(angle) * 1800.0)
+        .collider(
+            Collider {
+                shape: BoundingRect {
+                    width: size.x,
+                    height: size.y,
+                    .. Default::default()
+                }.into(),
+                team: DamageFilter::Player,
+                damage: 20,
+                kind: ObjectKind::Projectile,
+                despawn_on_hit: true,
             }
         )
-    }
+        .appearance(Color::RGB(230, 230, 30), size)
+        .explodes(Vec2::new(10.0, 10.0))
+        .despawn_offscreen()
 }
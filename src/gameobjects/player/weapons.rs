@@ -0,0 +1,678 @@
+use ::gameobjects::*;
+use ::gameobjects::object_table::{ObjectTable, ObjectKind};
+use ::gameobjects::raycast::Ray;
+use ::events::*;
+use ::view::*;
+use ::graphics::sprites::{VisibleComponent, VisibleRect};
+use ::graphics::backend::Texture;
+use ::time::*;
+use ::set::Intersects;
+
+use super::*;
+use super::ship::{Gun, GunArgs, GunStatus, bullet_stopped_by};
+
+use std::convert::TryInto;
+use sdl2::pixels::Color;
+
+const MISSILE_SPEED: f64 = 420.0;
+/// How long a homing missile flies before it burns out.
+const MISSILE_LIFETIME: u32 = 4000;
+
+pub struct HomingLevel {
+    pub fire_interval: u32,
+    pub missiles: u32,
+    pub damage: u32,
+    /// How quickly missiles turn towards their target, as the fraction of
+    /// the way they turn each second.
+    pub turn_rate: f64,
+}
+
+pub static HOMING_LEVELS: [HomingLevel; 3] = [
+    HomingLevel {
+        fire_interval: 700,
+        missiles: 2,
+        damage: 30,
+        turn_rate: 3.0,
+    },
+    HomingLevel {
+        fire_interval: 600,
+        missiles: 2,
+        damage: 35,
+        turn_rate: 4.0,
+    },
+    HomingLevel {
+        fire_interval: 500,
+        missiles: 4,
+        damage: 40,
+        turn_rate: 5.0,
+    },
+];
+
+/// Fires missiles that steer towards the nearest enemy.
+pub struct HomingLauncher {
+    pub last_shot_at: u32,
+    pub level: usize,
+}
+
+impl HomingLauncher {
+    pub fn new(now: u32) -> Self {
+        HomingLauncher {
+            last_shot_at: now,
+            level: 0,
+        }
+    }
+
+    pub fn build(now: u32) -> Box<Gun> {
+        box HomingLauncher::new(now)
+    }
+
+    pub fn stats(&self) -> &'static HomingLevel {
+        &HOMING_LEVELS[self.level]
+    }
+}
+
+impl Gun for HomingLauncher {
+    fn spawn_bullets(
+        &mut self,
+        args: GunArgs,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let stats = self.stats();
+
+        if time.total - self.last_shot_at < stats.fire_interval {
+            return vec![];
+        }
+
+        self.last_shot_at = time.total;
+
+        let origin = args.bounds.center();
+
+        let missiles = (0..stats.missiles).map(|i| {
+            // Missiles leave the ship fanned out above and below it, and
+            // curve round towards their targets from there
+            let side = if i % 2 == 0 { -1.0 } else { 1.0 };
+            let angle = side * (0.6 + 0.3 * (i / 2) as f64);
+
+            box HomingMissile::new(
+                origin,
+                Vec2::from_angle(angle) * MISSILE_SPEED,
+                stats,
+                time.total
+            ) as SimpleObject
+        }).collect();
+
+        vec![GameAction::AddObjects(missiles)]
+    }
+
+    fn name(&self) -> &'static str { "Homing" }
+
+    fn upgrade(&mut self) {
+        if self.level + 1 < HOMING_LEVELS.len() {
+            self.level += 1;
+        }
+    }
+
+    fn downgrade(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    fn status(&self) -> GunStatus {
+        let stats = self.stats();
+
+        GunStatus {
+            name: self.name(),
+            level: self.level,
+            max_level: HOMING_LEVELS.len() - 1,
+            details: format!(
+                "{} missiles, {} damage",
+                stats.missiles,
+                stats.damage
+            ),
+        }
+    }
+}
+
+pub struct HomingMissile {
+    pub bounds: BoundingRect,
+    pub velocity: Vec2,
+    pub turn_rate: f64,
+    pub damage: u32,
+    pub expires_at: u32,
+}
+
+impl HomingMissile {
+    pub fn new(
+        center: Vec2,
+        velocity: Vec2,
+        stats: &HomingLevel,
+        now: u32
+    ) -> Self {
+        HomingMissile {
+            bounds: BoundingRect {
+                width: 10.0,
+                height: 4.0,
+                .. Default::default()
+            }.centered_at(center),
+            velocity: velocity,
+            turn_rate: stats.turn_rate,
+            damage: stats.damage,
+            expires_at: now + MISSILE_LIFETIME,
+        }
+    }
+}
+
+impl GameObject<Keys, Texture> for HomingMissile {
+    fn update(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let dt = time.elapsed.exact_seconds();
+        let center = self.bounds.center();
+
+        let target = context.objects.nearest(center, ObjectKind::Enemy)
+            .and_then(|enemy| enemy.bounds)
+            .map(|b| b.center());
+
+        if let Some(target) = target {
+            let desired = (target - center).normalize() * MISSILE_SPEED;
+
+            self.velocity = self.velocity.lerp(
+                desired,
+                (self.turn_rate * dt).min(1.0)
+            ).normalize() * MISSILE_SPEED;
+        }
+
+        self.bounds.translate(self.velocity * dt);
+
+        let screen = context.play_area;
+
+        if time.total < self.expires_at && self.bounds.intersects(&screen) {
+            vec![]
+        } else {
+            vec![GameAction::Delete]
+        }
+    }
+
+    fn sprites(&self, _: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        vec![
+            (
+                VisibleRect(Color::RGB(250, 150, 40)).into(),
+                self.bounds.try_into().unwrap(),
+            )
+        ]
+    }
+
+    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Projectile }
+
+    fn receive_message<'a>(
+        &'a mut self,
+        ctx: &mut Context<Keys>,
+        time: GameTime,
+        m: GameMessage<'a, Keys, Texture>
+    ) -> Vec<GameAction<Keys, Texture>> {
+        if !bullet_stopped_by(&m) {
+            vec![]
+        } else {
+            vec![
+                GameAction::Delete,
+                GameAction::AddObjects(
+                    vec![
+                        box Explosion::with_bounds(
+                            &mut ctx.renderer,
+                            time.total,
+                            BoundingRect {
+                                width: 24.0,
+                                height: 24.0,
+                                .. Default::default()
+                            }.centered_at(self.bounds.center())
+                        )
+                    ]
+                ),
+            ]
+        }
+    }
+
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        Some(
+            GameMessage::Hit {
+                other: self as _,
+                info: DamageInfo {
+                    damage: self.damage,
+                    filter: DamageFilter::Player,
+                },
+            }
+        )
+    }
+}
+
+pub struct LaserLevel {
+    /// Damage done every frame that the beam touches something.
+    pub damage: u32,
+    pub width: f64,
+    /// How many enemies the beam passes through before it's stopped.
+    pub pierce: usize,
+}
+
+pub static LASER_LEVELS: [LaserLevel; 3] = [
+    LaserLevel {
+        damage: 1,
+        width: 4.0,
+        pierce: 1,
+    },
+    LaserLevel {
+        damage: 1,
+        width: 6.0,
+        pierce: 2,
+    },
+    LaserLevel {
+        damage: 2,
+        width: 8.0,
+        pierce: 4,
+    },
+];
+
+/// Fires a continuous beam for as long as the trigger is held.
+pub struct Laser {
+    pub level: usize,
+}
+
+impl Laser {
+    pub fn new() -> Self {
+        Laser { level: 0 }
+    }
+
+    pub fn build(_: u32) -> Box<Gun> {
+        box Laser::new()
+    }
+
+    pub fn stats(&self) -> &'static LaserLevel {
+        &LASER_LEVELS[self.level]
+    }
+}
+
+impl Gun for Laser {
+    fn spawn_bullets(
+        &mut self,
+        args: GunArgs,
+        _: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let origin = Vec2::new(args.bounds.right(), args.bounds.center().y);
+
+        vec![
+            GameAction::AddObjects(
+                vec![
+                    box LaserBeam::new(
+                        origin,
+                        self.stats(),
+                        args.objects,
+                        args.play_area
+                    )
+                ]
+            ),
+        ]
+    }
+
+    fn name(&self) -> &'static str { "Laser" }
+
+    fn upgrade(&mut self) {
+        if self.level + 1 < LASER_LEVELS.len() {
+            self.level += 1;
+        }
+    }
+
+    fn downgrade(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    fn status(&self) -> GunStatus {
+        let stats = self.stats();
+
+        GunStatus {
+            name: self.name(),
+            level: self.level,
+            max_level: LASER_LEVELS.len() - 1,
+            details: format!(
+                "pierces {}, {} damage/tick",
+                stats.pierce,
+                stats.damage
+            ),
+        }
+    }
+}
+
+/// One frame of a `Laser`'s beam. The laser fires a new one every frame.
+/// Each one is drawn in the frame it's fired, damages whatever it covers in
+/// the next, and then goes away.
+pub struct LaserBeam {
+    pub width: f64,
+    pub damage: u32,
+    pub bounds: BoundingRect,
+}
+
+impl LaserBeam {
+    /// A beam from `origin` to the edge of `play_area`, unless it's stopped
+    /// before then by enough of the enemies in `objects`.
+    pub fn new(
+        origin: Vec2,
+        stats: &LaserLevel,
+        objects: &ObjectTable,
+        play_area: BoundingRect
+    ) -> Self {
+        let ray = Ray::new(origin, Vec2::new(1.0, 0.0))
+            .with_length((play_area.right() - origin.x).max(0.0));
+
+        // The beam reaches a little way into whatever stops it, so that
+        // that gets hit too
+        let length = objects.raycast_all(&ray, ObjectKind::Enemy)
+            .get(stats.pierce)
+            .map_or(ray.length, |&(_, d)| (d + stats.width).min(ray.length));
+
+        LaserBeam {
+            width: stats.width,
+            damage: stats.damage,
+            bounds: BoundingRect {
+                x: origin.x,
+                y: origin.y - stats.width / 2.0,
+                width: length,
+                height: stats.width,
+            },
+        }
+    }
+}
+
+impl GameObject<Keys, Texture> for LaserBeam {
+    fn update(
+        &mut self,
+        _: &mut Context<Keys>,
+        _: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        // Deleting only happens once the tick's collisions are done, so the
+        // beam still hits things this tick
+        vec![GameAction::Delete]
+    }
+
+    fn sprites(&self, _: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        // Written so that NaN widths are skipped too
+        if !(self.bounds.width > 0.0) { return vec![]; }
+
+        let core = BoundingRect {
+            y: self.bounds.center().y - self.width / 4.0,
+            height: self.width / 2.0,
+            .. self.bounds
+        };
+
+        vec![
+            (
+                VisibleRect(Color::RGB(60, 200, 255)).into(),
+                self.bounds.try_into().unwrap(),
+            ),
+            (
+                VisibleRect(Color::RGB(230, 250, 255)).into(),
+                core.try_into().unwrap(),
+            ),
+        ]
+    }
+
+    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Projectile }
+
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        Some(
+            GameMessage::Hit {
+                other: self as _,
+                info: DamageInfo {
+                    damage: self.damage,
+                    filter: DamageFilter::Player,
+                },
+            }
+        )
+    }
+}
+
+pub struct ChargeLevel {
+    /// How long it takes to charge fully.
+    pub charge_time: u32,
+    /// The damage done by a shot fired straight away.
+    pub min_damage: u32,
+    /// The damage done by a fully charged shot.
+    pub max_damage: u32,
+}
+
+pub static CHARGE_LEVELS: [ChargeLevel; 3] = [
+    ChargeLevel {
+        charge_time: 1200,
+        min_damage: 15,
+        max_damage: 120,
+    },
+    ChargeLevel {
+        charge_time: 1000,
+        min_damage: 20,
+        max_damage: 160,
+    },
+    ChargeLevel {
+        charge_time: 800,
+        min_damage: 25,
+        max_damage: 220,
+    },
+];
+
+/// How long the trigger can go without being held before the charge is
+/// lost, such as when switching to another weapon and back.
+const CHARGE_GAP: u32 = 50;
+
+/// Charges up while the trigger is held, and fires a single shot when it's
+/// let go. The longer the charge, the bigger and stronger the shot.
+pub struct ChargeCannon {
+    pub charging_since: Option<u32>,
+    pub held_at: u32,
+    /// How charged the gun is, from 0 to 1.
+    pub charge: f64,
+    pub level: usize,
+}
+
+impl ChargeCannon {
+    pub fn new() -> Self {
+        ChargeCannon {
+            charging_since: None,
+            held_at: 0,
+            charge: 0.0,
+            level: 0,
+        }
+    }
+
+    pub fn build(_: u32) -> Box<Gun> {
+        box ChargeCannon::new()
+    }
+
+    pub fn stats(&self) -> &'static ChargeLevel {
+        &CHARGE_LEVELS[self.level]
+    }
+
+    fn is_held(&self, now: u32) -> bool {
+        self.charging_since.is_some() && now - self.held_at <= CHARGE_GAP
+    }
+}
+
+impl Gun for ChargeCannon {
+    fn spawn_bullets(
+        &mut self,
+        _: GunArgs,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        if !self.is_held(time.total) {
+            self.charging_since = Some(time.total);
+        }
+
+        self.held_at = time.total;
+
+        let charged_for = self.charging_since.map_or(0, |t| time.total - t);
+
+        self.charge = (
+            charged_for as f64 / self.stats().charge_time as f64
+        ).min(1.0);
+
+        vec![]
+    }
+
+    fn release(
+        &mut self,
+        args: GunArgs,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        let held = self.is_held(time.total);
+        let charge = self.charge;
+
+        self.charging_since = None;
+        self.charge = 0.0;
+
+        if !held { return vec![]; }
+
+        let stats = self.stats();
+        let damage = stats.min_damage + (
+            (stats.max_damage - stats.min_damage) as f64 * charge
+        ) as u32;
+
+        vec![
+            GameAction::AddObjects(
+                vec![
+                    box ChargeShot::new(
+                        Vec2::new(args.bounds.right(), args.bounds.center().y),
+                        8.0 + 24.0 * charge,
+                        damage
+                    )
+                ]
+            ),
+        ]
+    }
+
+    fn name(&self) -> &'static str { "Charge" }
+
+    fn upgrade(&mut self) {
+        if self.level + 1 < CHARGE_LEVELS.len() {
+            self.level += 1;
+        }
+    }
+
+    fn downgrade(&mut self) {
+        self.level = self.level.saturating_sub(1);
+    }
+
+    fn status(&self) -> GunStatus {
+        let stats = self.stats();
+
+        GunStatus {
+            name: self.name(),
+            level: self.level,
+            max_level: CHARGE_LEVELS.len() - 1,
+            details: format!(
+                "charge {:.0}%, {}-{} damage",
+                self.charge * 100.0,
+                stats.min_damage,
+                stats.max_damage
+            ),
+        }
+    }
+}
+
+pub struct ChargeShot {
+    pub bounds: BoundingRect,
+    pub velocity: Vec2,
+    pub damage: u32,
+}
+
+impl ChargeShot {
+    pub fn new(center: Vec2, size: f64, damage: u32) -> Self {
+        ChargeShot {
+            bounds: BoundingRect {
+                width: size,
+                height: size * 0.6,
+                .. Default::default()
+            }.centered_at(center),
+            velocity: Vec2::new(900.0, 0.0),
+            damage: damage,
+        }
+    }
+}
+
+impl GameObject<Keys, Texture> for ChargeShot {
+    fn update(
+        &mut self,
+        context: &mut Context<Keys>,
+        time: GameTime
+    ) -> Vec<GameAction<Keys, Texture>> {
+        self.bounds.translate(self.velocity * time.elapsed.exact_seconds());
+
+        let screen = context.play_area;
+
+        if self.bounds.intersects(&screen) {
+            vec![]
+        } else {
+            vec![GameAction::Delete]
+        }
+    }
+
+    fn sprites(&self, _: GameTime)
+        -> Vec<(VisibleComponent<Texture>, Dest)>
+    {
+        vec![
+            (
+                VisibleRect(Color::RGB(240, 120, 250)).into(),
+                self.bounds.try_into().unwrap(),
+            )
+        ]
+    }
+
+    fn bounds(&self) -> Option<Bounds> { Some(self.bounds.into()) }
+
+    fn kind(&self) -> ObjectKind { ObjectKind::Projectile }
+
+    fn receive_message<'a>(
+        &'a mut self,
+        ctx: &mut Context<Keys>,
+        time: GameTime,
+        m: GameMessage<'a, Keys, Texture>
+    ) -> Vec<GameAction<Keys, Texture>> {
+        if !bullet_stopped_by(&m) {
+            vec![]
+        } else {
+            let size = self.bounds.width * 2.0;
+
+            vec![
+                GameAction::Delete,
+                GameAction::AddObjects(
+                    vec![
+                        box Explosion::with_bounds(
+                            &mut ctx.renderer,
+                            time.total,
+                            BoundingRect {
+                                width: size,
+                                height: size,
+                                .. Default::default()
+                            }.centered_at(self.bounds.center())
+                        )
+                    ]
+                ),
+            ]
+        }
+    }
+
+    fn on_hit(&self) -> Option<GameMessage<Keys, Texture>> {
+        Some(
+            GameMessage::Hit {
+                other: self as _,
+                info: DamageInfo {
+                    damage: self.damage,
+                    filter: DamageFilter::Player,
+                },
+            }
+        )
+    }
+}
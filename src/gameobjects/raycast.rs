@@ -0,0 +1,289 @@
+use ::gameobjects::{Bounds, Circle, Rectangle, Vec2};
+
+use std::f64;
+use std::cmp::Ordering;
+
+/// A half-line starting at `origin`, cut off after `length`. `direction` is
+/// always normalized, so distances along the ray are in world units.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec2,
+    pub direction: Vec2,
+    pub length: f64,
+}
+
+impl Ray {
+    pub fn new(origin: Vec2, direction: Vec2) -> Self {
+        Ray {
+            origin: origin,
+            direction: direction.normalize(),
+            length: f64::INFINITY,
+        }
+    }
+
+    pub fn segment(from: Vec2, to: Vec2) -> Self {
+        Ray {
+            length: from.distance(to),
+            .. Ray::new(from, to - from)
+        }
+    }
+
+    pub fn with_length(&self, length: f64) -> Self {
+        Ray {
+            length: length,
+            .. *self
+        }
+    }
+
+    pub fn point_at(&self, distance: f64) -> Vec2 {
+        self.origin + self.direction * distance
+    }
+
+    pub fn end(&self) -> Vec2 {
+        self.point_at(self.length)
+    }
+}
+
+pub trait RayCast {
+    /// The distance along `ray` at which it first touches `self`, if it does
+    /// so before running out. Rays starting inside a shape hit it at `0.0`.
+    fn cast(&self, ray: &Ray) -> Option<f64>;
+}
+
+impl RayCast for Rectangle<f64> {
+    fn cast(&self, ray: &Ray) -> Option<f64> {
+        let (mut near, mut far) = (0.0, ray.length);
+
+        for &(origin, dir, min, max) in &[
+            (ray.origin.x, ray.direction.x, self.left(), self.right()),
+            (ray.origin.y, ray.direction.y, self.top(), self.bottom()),
+        ] {
+            // A slab with a NaN edge can't be inside or outside of anything
+            if min.is_nan() || max.is_nan() { return None; }
+
+            if dir == 0.0 {
+                if origin < min || origin > max { return None; }
+            } else {
+                let (a, b) = ((min - origin) / dir, (max - origin) / dir);
+                let (a, b) = if a < b { (a, b) } else { (b, a) };
+
+                if a > near { near = a; }
+                if b < far { far = b; }
+
+                if near > far { return None; }
+            }
+        }
+
+        Some(near)
+    }
+}
+
+impl RayCast for Circle<f64> {
+    fn cast(&self, ray: &Ray) -> Option<f64> {
+        let offset = ray.origin - self.center();
+
+        // `direction` is normalized, so the quadratic's `a` term is 1
+        let b = offset.dot(ray.direction);
+        let c = offset.length_squared() - self.radius * self.radius;
+
+        if c <= 0.0 { return Some(0.0); }
+
+        let discriminant = b * b - c;
+
+        if discriminant < 0.0 { return None; }
+
+        let t = -b - discriminant.sqrt();
+
+        if t >= 0.0 && t <= ray.length {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
+
+impl RayCast for Bounds {
+    fn cast(&self, ray: &Ray) -> Option<f64> {
+        match *self {
+            Bounds::Rectangle(ref r) => r.cast(ray),
+            Bounds::Circle(ref c) => c.cast(ray),
+        }
+    }
+}
+
+/// The distance along `ray` that `bounds` are hit at, if they're hit at all.
+/// NaN distances don't count as hits, since they can't be put in order with
+/// the others.
+fn hit_distance(bounds: Option<Bounds>, ray: &Ray) -> Option<f64> {
+    bounds.and_then(|b| b.cast(ray)).and_then(|d|
+        if d.is_nan() { None } else { Some(d) }
+    )
+}
+
+/// Every item hit by `ray`, nearest first.
+pub fn all_hits<'a, T: 'a + ?Sized, I, B>(
+    items: I,
+    ray: &Ray,
+    mut bounds: B
+) -> Vec<(&'a T, f64)>
+    where
+        I: IntoIterator<Item=&'a T>,
+        B: FnMut(&T) -> Option<Bounds>
+{
+    let mut hits = items.into_iter().filter_map(
+        |item| hit_distance(bounds(item), ray).map(|d| (item, d))
+    ).collect::<Vec<_>>();
+
+    hits.sort_by(|&(_, a), &(_, b)|
+        a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+    );
+
+    hits
+}
+
+/// The nearest item hit by `ray`, and its distance along it.
+pub fn first_hit<'a, T: 'a + ?Sized, I, B>(
+    items: I,
+    ray: &Ray,
+    mut bounds: B
+) -> Option<(&'a T, f64)>
+    where
+        I: IntoIterator<Item=&'a T>,
+        B: FnMut(&T) -> Option<Bounds>
+{
+    items.into_iter().fold(
+        None,
+        |nearest, item| match (
+            nearest,
+            hit_distance(bounds(item), ray)
+        ) {
+            (Some((_, best)), Some(d)) if d < best => Some((item, d)),
+            (None, Some(d)) => Some((item, d)),
+            (nearest, _) => nearest,
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{all_hits, first_hit, Ray, RayCast};
+    use ::gameobjects::{BoundingRect, Bounds, Circle, Vec2};
+
+    use std::f64;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Bounds {
+        BoundingRect {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+        }.into()
+    }
+
+    fn circle(x: f64, y: f64, radius: f64) -> Bounds {
+        Circle {
+            x: x,
+            y: y,
+            radius: radius,
+        }.into()
+    }
+
+    fn right_from(x: f64, y: f64) -> Ray {
+        Ray::new(Vec2::new(x, y), Vec2::new(1.0, 0.0))
+    }
+
+    #[test]
+    fn rays_hit_the_near_edge_of_rectangles() {
+        let ray = right_from(0.0, 0.0);
+        let diagonal = Ray::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+        let d = rect(10.0, 10.0, 10.0, 10.0).cast(&diagonal).unwrap();
+
+        assert_eq!(rect(10.0, -5.0, 10.0, 10.0).cast(&ray), Some(10.0));
+        assert_eq!(rect(-20.0, -5.0, 10.0, 10.0).cast(&ray), None);
+        assert!((d - 10.0 * 2.0f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rays_hit_the_near_side_of_circles() {
+        let ray = right_from(0.0, 0.0);
+
+        assert_eq!(circle(10.0, 0.0, 2.0).cast(&ray), Some(8.0));
+        assert_eq!(circle(10.0, 5.0, 2.0).cast(&ray), None);
+        assert_eq!(circle(-10.0, 0.0, 2.0).cast(&ray), None);
+    }
+
+    #[test]
+    fn axis_parallel_rays_outside_the_slab_miss() {
+        let right = right_from(0.0, 0.0);
+        let down = Ray::new(Vec2::new(0.0, 0.0), Vec2::new(0.0, 1.0));
+
+        assert_eq!(rect(10.0, 10.0, 10.0, 10.0).cast(&right), None);
+        assert_eq!(rect(10.0, 10.0, 10.0, 10.0).cast(&down), None);
+        assert_eq!(rect(-5.0, 10.0, 10.0, 10.0).cast(&down), Some(10.0));
+    }
+
+    #[test]
+    fn rays_starting_inside_hit_straight_away() {
+        let ray = right_from(0.0, 0.0);
+
+        assert_eq!(rect(-5.0, -5.0, 10.0, 10.0).cast(&ray), Some(0.0));
+        assert_eq!(circle(1.0, 1.0, 5.0).cast(&ray), Some(0.0));
+    }
+
+    #[test]
+    fn rays_stop_at_their_length() {
+        let ray = right_from(0.0, 0.0).with_length(5.0);
+        let segment = Ray::segment(Vec2::new(0.0, 0.0), Vec2::new(12.0, 0.0));
+
+        assert_eq!(rect(10.0, -5.0, 10.0, 10.0).cast(&ray), None);
+        assert_eq!(circle(10.0, 0.0, 2.0).cast(&ray), None);
+        assert_eq!(rect(10.0, -5.0, 10.0, 10.0).cast(&segment), Some(10.0));
+        assert_eq!(segment.end(), Vec2::new(12.0, 0.0));
+    }
+
+    #[test]
+    fn nan_bounds_are_never_hit() {
+        let nan = f64::NAN;
+        let ray = right_from(0.0, 0.0);
+        let items = [rect(nan, nan, nan, nan), circle(nan, nan, nan)];
+
+        assert_eq!(items[0].cast(&ray), None);
+        assert_eq!(items[1].cast(&ray), None);
+        assert!(all_hits(&items, &ray, |b| Some(*b)).is_empty());
+        assert!(first_hit(&items, &ray, |b| Some(*b)).is_none());
+    }
+
+    #[test]
+    fn hits_are_found_nearest_first() {
+        let ray = right_from(0.0, 0.0);
+        let items = [
+            circle(30.0, 0.0, 2.0),
+            rect(10.0, -5.0, 5.0, 10.0),
+            circle(0.0, 20.0, 2.0),
+            rect(20.0, -5.0, 5.0, 10.0),
+        ];
+
+        let hits = all_hits(&items, &ray, |b| Some(*b)).into_iter()
+            .map(|(b, d)| (*b, d))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            hits,
+            vec![(items[1], 10.0), (items[3], 20.0), (items[0], 28.0)]
+        );
+        assert_eq!(
+            first_hit(&items, &ray, |b| Some(*b)).map(|(b, d)| (*b, d)),
+            Some((items[1], 10.0))
+        );
+        assert!(first_hit(&items[2..3], &ray, |b| Some(*b)).is_none());
+    }
+
+    #[test]
+    fn items_without_bounds_are_skipped() {
+        let ray = right_from(0.0, 0.0);
+        let items = [rect(10.0, -5.0, 5.0, 10.0)];
+
+        assert!(all_hits(&items, &ray, |_| None).is_empty());
+        assert!(first_hit(&items, &ray, |_| None).is_none());
+    }
+}
@@ -0,0 +1,210 @@
+use ::gameobjects::{Circle, Rectangle};
+use ::fixed_size_iter::FixedSizeIntoMap;
+
+use std::ops::{
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Neg,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec2 {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Vec2 {
+    pub fn new(x: f64, y: f64) -> Self {
+        Vec2 { x: x, y: y }
+    }
+
+    pub fn zero() -> Self {
+        Vec2::new(0.0, 0.0)
+    }
+
+    /// The unit vector pointing `angle` radians clockwise from the positive x
+    /// axis (clockwise because y points down the screen).
+    pub fn from_angle(angle: f64) -> Self {
+        Vec2::new(angle.cos(), angle.sin())
+    }
+
+    pub fn length_squared(&self) -> f64 {
+        self.dot(*self)
+    }
+
+    pub fn length(&self) -> f64 {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance(&self, other: Vec2) -> f64 {
+        (other - *self).length()
+    }
+
+    /// The unit vector in the same direction, or zero for the zero vector.
+    pub fn normalize(&self) -> Self {
+        let len = self.length();
+
+        if len == 0.0 {
+            Vec2::zero()
+        } else {
+            *self / len
+        }
+    }
+
+    pub fn dot(&self, other: Vec2) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z component of the 3D cross product, positive when `other` is
+    /// clockwise of `self` on screen.
+    pub fn cross(&self, other: Vec2) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn angle(&self) -> f64 {
+        self.y.atan2(self.x)
+    }
+
+    pub fn rotate(&self, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        Vec2::new(
+            self.x * cos - self.y * sin,
+            self.x * sin + self.y * cos
+        )
+    }
+
+    pub fn lerp(&self, other: Vec2, t: f64) -> Self {
+        *self + (other - *self) * t
+    }
+}
+
+impl From<[f64; 2]> for Vec2 {
+    fn from([x, y]: [f64; 2]) -> Self {
+        Vec2::new(x, y)
+    }
+}
+
+impl From<Vec2> for [f64; 2] {
+    fn from(v: Vec2) -> Self {
+        [v.x, v.y]
+    }
+}
+
+impl FixedSizeIntoMap<f64> for Vec2 {
+    type Item = f64;
+    type Out = Vec2;
+
+    fn map<F: FnMut(f64) -> f64>(self, mut f: F) -> Vec2 {
+        Vec2::new(f(self.x), f(self.y))
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        Vec2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f64> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, scale: f64) -> Vec2 {
+        Vec2::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl Mul<Vec2> for f64 {
+    type Output = Vec2;
+
+    fn mul(self, v: Vec2) -> Vec2 {
+        v * self
+    }
+}
+
+impl Div<f64> for Vec2 {
+    type Output = Vec2;
+
+    fn div(self, scale: f64) -> Vec2 {
+        Vec2::new(self.x / scale, self.y / scale)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Vec2) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, other: Vec2) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign<f64> for Vec2 {
+    fn mul_assign(&mut self, scale: f64) {
+        *self = *self * scale;
+    }
+}
+
+impl Rectangle<f64> {
+    pub fn position(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height)
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.position() + self.size() / 2.0
+    }
+
+    pub fn translate(&mut self, by: Vec2) {
+        self.x += by.x;
+        self.y += by.y;
+    }
+
+    pub fn at(&self, position: Vec2) -> Self {
+        self.with_position(position.x, position.y)
+    }
+
+    pub fn centered_at(&self, center: Vec2) -> Self {
+        self.at(center - self.size() / 2.0)
+    }
+}
+
+impl Circle<f64> {
+    pub fn center(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    pub fn translate(&mut self, by: Vec2) {
+        self.x += by.x;
+        self.y += by.y;
+    }
+}
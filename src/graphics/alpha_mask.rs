@@ -0,0 +1,268 @@
+use ::gameobjects::{Clip, Dest};
+use ::set::Set;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use sdl2::surface::Surface;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::BlendMode;
+use sdl2_image::LoadSurface;
+
+/// Pixels with an alpha at or below this are treated as empty space.
+pub const DEFAULT_ALPHA_THRESHOLD: u8 = 32;
+
+/// A 1-bit-per-pixel record of which pixels of a sprite frame are solid.
+/// Rows are packed into `u64`s so that unscaled overlap tests can compare 64
+/// pixels at a time.
+#[derive(Clone, Debug)]
+pub struct AlphaMask {
+    pub width: u32,
+    pub height: u32,
+    words_per_row: u32,
+    bits: Vec<u64>,
+}
+
+impl AlphaMask {
+    /// Builds a mask from the `region` of an alpha channel that is
+    /// `stride` pixels wide.
+    pub fn from_alpha(
+        alpha: &[u8],
+        stride: u32,
+        region: Clip,
+        threshold: u8
+    ) -> Self {
+        let words_per_row = (region.width + 63) / 64;
+        let mut bits = vec![0; (words_per_row * region.height) as usize];
+
+        for y in 0..region.height {
+            for x in 0..region.width {
+                if region.x + x >= stride { break; }
+
+                let i = (region.y + y) * stride + region.x + x;
+
+                if alpha.get(i as usize).map_or(false, |&a| a > threshold) {
+                    let word = y * words_per_row + x / 64;
+
+                    bits[word as usize] |= 1 << (x % 64);
+                }
+            }
+        }
+
+        AlphaMask {
+            width: region.width,
+            height: region.height,
+            words_per_row: words_per_row,
+            bits: bits,
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        x < self.width &&
+        y < self.height &&
+        self.bits[(y * self.words_per_row + x / 64) as usize] &
+            (1 << (x % 64)) != 0
+    }
+
+    /// The 64 pixels of row `y` starting at `x`, with bit `n` of the output
+    /// being pixel `x + n`. Pixels outside the mask are empty.
+    fn word_at(&self, x: u32, y: u32) -> u64 {
+        if y >= self.height { return 0; }
+
+        let row = &self.bits[
+            (y * self.words_per_row) as usize..
+            ((y + 1) * self.words_per_row) as usize
+        ];
+        let (i, shift) = ((x / 64) as usize, x % 64);
+
+        let lo = row.get(i).cloned().unwrap_or(0) >> shift;
+        let hi = if shift == 0 {
+            0
+        } else {
+            row.get(i + 1).cloned().unwrap_or(0) << (64 - shift)
+        };
+
+        lo | hi
+    }
+
+    /// Whether screen pixel `[x, y]` is solid when this mask is stretched
+    /// over `dest`.
+    pub fn solid_at(&self, dest: Dest, x: i32, y: i32) -> bool {
+        if
+            x < dest.left() || x >= dest.right() ||
+            y < dest.top() || y >= dest.bottom()
+        {
+            return false;
+        }
+
+        let (dx, dy) = ((x - dest.x) as u64, (y - dest.y) as u64);
+
+        self.get(
+            (dx * self.width as u64 / dest.width as u64) as _,
+            (dy * self.height as u64 / dest.height as u64) as _
+        )
+    }
+
+    fn is_unscaled(&self, dest: Dest) -> bool {
+        dest.width == self.width && dest.height == self.height
+    }
+
+    /// Whether any solid pixel of this mask drawn at `dest` lands on a solid
+    /// pixel of `other` drawn at `other_dest`.
+    pub fn overlaps(
+        &self,
+        dest: Dest,
+        other: &AlphaMask,
+        other_dest: Dest
+    ) -> bool {
+        use ::gameobjects::MinMax;
+
+        let area = if let Some(a) = dest.intersection(&other_dest) {
+            a
+        } else {
+            return false;
+        };
+
+        if self.is_unscaled(dest) && other.is_unscaled(other_dest) {
+            for y in area.top()..area.bottom() {
+                let mut x = area.left();
+
+                while x < area.right() {
+                    let len = (area.right() - x).min(64);
+                    let within = if len == 64 {
+                        !0
+                    } else {
+                        (1 << len) - 1
+                    };
+
+                    let a = self.word_at(
+                        (x - dest.x) as _,
+                        (y - dest.y) as _
+                    );
+                    let b = other.word_at(
+                        (x - other_dest.x) as _,
+                        (y - other_dest.y) as _
+                    );
+
+                    if a & b & within != 0 { return true; }
+
+                    x += 64;
+                }
+            }
+
+            false
+        } else {
+            (area.top()..area.bottom()).any(|y|
+                (area.left()..area.right()).any(|x|
+                    self.solid_at(dest, x, y) &&
+                    other.solid_at(other_dest, x, y)
+                )
+            )
+        }
+    }
+
+    /// Whether any solid pixel of this mask drawn at `dest` falls inside
+    /// `rect`, for colliding against objects with no mask of their own.
+    pub fn overlaps_rect(&self, dest: Dest, rect: Dest) -> bool {
+        if let Some(area) = dest.intersection(&rect) {
+            (area.top()..area.bottom()).any(|y|
+                (area.left()..area.right()).any(|x|
+                    self.solid_at(dest, x, y)
+                )
+            )
+        } else {
+            false
+        }
+    }
+}
+
+/// Reads the alpha channel of an image, one byte per pixel.
+pub fn load_alpha<P: AsRef<Path>>(
+    path: P
+) -> Result<(Vec<u8>, [u32; 2]), String> {
+    let mut src = try!(Surface::from_file(path.as_ref()));
+    let (w, h) = (src.width(), src.height());
+
+    // Blitting onto a surface of known format does the conversion for us, as
+    // long as the source isn't blended onto the (empty) destination.
+    try!(src.set_blend_mode(BlendMode::None));
+
+    let mut rgba = try!(Surface::new(w, h, PixelFormatEnum::RGBA8888));
+
+    try!(src.blit(None, &mut rgba, None));
+
+    let pitch = rgba.pitch() as usize;
+    // RGBA8888 is a packed format, so where the alpha byte lives depends on
+    // the endianness of the machine.
+    let alpha_byte = if cfg!(target_endian = "little") { 0 } else { 3 };
+
+    let alpha = rgba.with_lock(|pixels|
+        (0..h as usize).flat_map(|y|
+            (0..w as usize).map(move |x| y * pitch + x * 4 + alpha_byte)
+        ).map(|i| pixels[i]).collect()
+    );
+
+    Ok((alpha, [w, h]))
+}
+
+/// The mask equivalent of `build_spritesheet`, producing one mask per frame
+/// in the same order.
+pub fn build_spritesheet_masks<P: AsRef<Path>>(
+    path: P,
+    sprite_width: u32,
+    sprite_height: u32
+) -> Result<Vec<AlphaMask>, String> {
+    use itertools::*;
+
+    let (alpha, [w, h]) = try!(load_alpha(path));
+
+    Ok(
+        (0..h).step_by(sprite_height)
+            .cartesian_product((0..w).step_by(sprite_width))
+            .map(|(y, x)|
+                AlphaMask::from_alpha(
+                    &alpha,
+                    w,
+                    Clip {
+                        x: x,
+                        y: y,
+                        width: sprite_width,
+                        height: sprite_height,
+                    },
+                    DEFAULT_ALPHA_THRESHOLD
+                )
+            ).collect()
+    )
+}
+
+thread_local! {
+    static SHARED_MASKS: RefCell<
+        HashMap<(PathBuf, u32, u32), Rc<Vec<AlphaMask>>>
+    > = RefCell::new(HashMap::new());
+}
+
+/// Like `build_spritesheet_masks`, but only decodes each spritesheet the
+/// first time it's asked for, sharing the masks between everything that
+/// uses it after that.
+pub fn shared_spritesheet_masks<P: AsRef<Path>>(
+    path: P,
+    sprite_width: u32,
+    sprite_height: u32
+) -> Result<Rc<Vec<AlphaMask>>, String> {
+    let key = (path.as_ref().to_path_buf(), sprite_width, sprite_height);
+
+    let cached = SHARED_MASKS.with(|m| m.borrow().get(&key).cloned());
+
+    if let Some(masks) = cached {
+        return Ok(masks);
+    }
+
+    let masks = Rc::new(
+        try!(build_spritesheet_masks(path, sprite_width, sprite_height))
+    );
+
+    SHARED_MASKS.with(|m| m.borrow_mut().insert(key, masks.clone()));
+
+    Ok(masks)
+}
@@ -0,0 +1,369 @@
+use ::gameobjects::{Clip, Dest};
+use ::graphics::font_cache::FontCache;
+use ::graphics::sprites::{
+    CopyRenderable,
+    GetSize,
+    Sprite,
+    Transform,
+    VisibleComponent,
+    VisibleRect,
+};
+
+use std::any::Any;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use sdl2::pixels::Color;
+use sdl2::render::{TextureQuery, Renderer};
+use sdl2_image::LoadTexture;
+use sdl2_ttf::Sdl2TtfContext;
+
+/// A texture made by a `Backend`. What's inside is up to the backend, so it
+/// should only be drawn by the one that made it.
+pub struct Texture {
+    size: [u32; 2],
+    inner: Box<Any>,
+}
+
+impl Texture {
+    pub fn new<T: Any>(inner: T, size: [u32; 2]) -> Self {
+        Texture {
+            size: size,
+            inner: Box::new(inner),
+        }
+    }
+
+    /// Whatever the backend wrapped, if it's a `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.inner.downcast_ref()
+    }
+}
+
+impl GetSize for Texture {
+    fn size(&self) -> [u32; 2] {
+        self.size
+    }
+}
+
+/// Everything the game needs from whatever it's drawing with, so that the
+/// rest of it doesn't have to know.
+pub trait Backend {
+    /// Loads the image at `path` as a texture.
+    fn load_image(&mut self, path: &Path) -> Result<Texture, String>;
+
+    /// Draws `text` in `color` into a new texture, using the font at `font`
+    /// at `size` points.
+    fn render_text(
+        &mut self,
+        font: &'static str,
+        size: u16,
+        text: &str,
+        color: Color
+    ) -> Result<Texture, String>;
+
+    /// The size of what's being drawn to, in pixels.
+    fn screen_size(&self) -> Result<(u32, u32), String>;
+
+    /// Fills the whole screen with `color`.
+    fn clear_to(&mut self, color: Color);
+
+    fn draw(&mut self, sprite: &VisibleComponent<Texture>, dest: Dest);
+
+    /// Shows everything drawn since the last time this was called.
+    fn present(&mut self);
+}
+
+/// Draws with SDL. Its textures wrap SDL textures, and it keeps the fonts
+/// that text is drawn with.
+pub struct SdlBackend<'a> {
+    /// For the things that only make sense for SDL, like fitting the
+    /// display to the window and capturing frames.
+    pub renderer: Renderer<'a>,
+    fonts: FontCache<'a>,
+}
+
+impl<'a> SdlBackend<'a> {
+    pub fn new(renderer: Renderer<'a>, ttf: &'a Sdl2TtfContext) -> Self {
+        SdlBackend {
+            renderer: renderer,
+            fonts: FontCache::new(ttf),
+        }
+    }
+}
+
+impl<'a> Backend for SdlBackend<'a> {
+    fn load_image(&mut self, path: &Path) -> Result<Texture, String> {
+        self.renderer.load_texture(path).map(|texture| {
+            let TextureQuery { width: w, height: h, .. } = texture.query();
+
+            Texture::new(texture, [w, h])
+        })
+    }
+
+    fn render_text(
+        &mut self,
+        font: &'static str,
+        size: u16,
+        text: &str,
+        color: Color
+    ) -> Result<Texture, String> {
+        let fonts = try!(self.fonts.with_loaded(font, size));
+        let surface = try!(
+            fonts.get(font, size).unwrap().render(text).blended(color)
+                .map_err(|e| format!("{:?}", e))
+        );
+        let texture = try!(
+            self.renderer.create_texture_from_surface(&surface)
+                .map_err(|e| e.to_string())
+        );
+
+        Ok(Texture::new(texture, [surface.width(), surface.height()]))
+    }
+
+    fn screen_size(&self) -> Result<(u32, u32), String> {
+        self.renderer.output_size()
+    }
+
+    fn clear_to(&mut self, color: Color) {
+        self.renderer.set_draw_color(color);
+        self.renderer.clear();
+    }
+
+    fn draw(&mut self, sprite: &VisibleComponent<Texture>, dest: Dest) {
+        self.renderer.copy_renderable(sprite, dest);
+    }
+
+    fn present(&mut self) {
+        self.renderer.present();
+    }
+}
+
+/// Where one of a `RecordingBackend`'s textures came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TextureSource {
+    Image(PathBuf),
+    Text(String),
+}
+
+/// Something a `RecordingBackend` was asked to do.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand {
+    Clear(Color),
+    Sprite {
+        texture: TextureSource,
+        mask: Clip,
+        dest: Dest,
+        transform: Transform,
+    },
+    Rect {
+        color: Color,
+        dest: Dest,
+    },
+    Present,
+}
+
+/// A backend that draws nothing, and instead keeps a list of what it was
+/// asked to draw. Images are only read far enough to find out their size,
+/// so it works without a window, or SDL at all.
+pub struct RecordingBackend {
+    pub screen_size: (u32, u32),
+    pub commands: Vec<DrawCommand>,
+    /// How many times `present` has been called.
+    pub frames: usize,
+}
+
+impl RecordingBackend {
+    pub fn new(screen_size: (u32, u32)) -> Self {
+        RecordingBackend {
+            screen_size: screen_size,
+            commands: vec![],
+            frames: 0,
+        }
+    }
+
+    /// Everything recorded so far, leaving the list empty.
+    pub fn take_commands(&mut self) -> Vec<DrawCommand> {
+        ::std::mem::replace(&mut self.commands, vec![])
+    }
+
+    /// What's been drawn of the current frame, since the last `present`,
+    /// one command to a line. This is how frames are captured without a
+    /// window, to check against golden snapshots.
+    pub fn snapshot(&self) -> String {
+        let start = self.commands.iter()
+            .rposition(|c| *c == DrawCommand::Present)
+            .map_or(0, |i| i + 1);
+
+        self.commands[start..].iter()
+            .map(|c| format!("{:?}\n", c))
+            .collect()
+    }
+
+    /// `None` if the texture was made by another backend, since there's no
+    /// telling where it came from.
+    fn sprite_command(
+        sprite: &Sprite<Texture>,
+        transform: Transform,
+        dest: Dest
+    ) -> Option<DrawCommand> {
+        sprite.texture.downcast_ref::<TextureSource>().map(|source|
+            DrawCommand::Sprite {
+                texture: source.clone(),
+                mask: sprite.mask,
+                dest: dest,
+                transform: transform,
+            }
+        )
+    }
+}
+
+/// The width and height of the PNG image at `path`, read from its header.
+fn png_size(path: &Path) -> Result<[u32; 2], String> {
+    const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+    let mut header = [0; 24];
+
+    try!(
+        File::open(path)
+            .and_then(|mut file| file.read_exact(&mut header))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    );
+
+    if &header[..8] != &SIGNATURE[..] || &header[12..16] != &b"IHDR"[..] {
+        return Err(format!("{} isn't a PNG image", path.display()));
+    }
+
+    let be = |bytes: &[u8]|
+        bytes.iter().fold(0, |acc, &b| (acc << 8) | b as u32);
+
+    Ok([be(&header[16..20]), be(&header[20..24])])
+}
+
+impl Backend for RecordingBackend {
+    fn load_image(&mut self, path: &Path) -> Result<Texture, String> {
+        png_size(path).map(|size|
+            Texture::new(TextureSource::Image(path.to_path_buf()), size)
+        )
+    }
+
+    /// Text is sized as if every character were half as wide as it is tall,
+    /// since there's no font to measure it with.
+    fn render_text(
+        &mut self,
+        _: &'static str,
+        size: u16,
+        text: &str,
+        _: Color
+    ) -> Result<Texture, String> {
+        let width = text.chars().count() as u32 * size as u32 / 2;
+
+        Ok(
+            Texture::new(
+                TextureSource::Text(text.to_string()),
+                [width, size as u32]
+            )
+        )
+    }
+
+    fn screen_size(&self) -> Result<(u32, u32), String> {
+        Ok(self.screen_size)
+    }
+
+    fn clear_to(&mut self, color: Color) {
+        self.commands.push(DrawCommand::Clear(color));
+    }
+
+    fn draw(&mut self, sprite: &VisibleComponent<Texture>, dest: Dest) {
+        let command = match *sprite {
+            VisibleComponent::Sprite(ref s) =>
+                Self::sprite_command(s, Transform::default(), dest),
+            VisibleComponent::Transformed(ref s, transform) =>
+                Self::sprite_command(s, transform, dest),
+            VisibleComponent::Rectangle(VisibleRect(color)) =>
+                Some(
+                    DrawCommand::Rect {
+                        color: color,
+                        dest: dest,
+                    }
+                ),
+        };
+
+        self.commands.extend(command);
+    }
+
+    fn present(&mut self) {
+        self.commands.push(DrawCommand::Present);
+        self.frames += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backend, DrawCommand, RecordingBackend, TextureSource};
+    use ::events::Keys;
+    use ::view::{Action, Context, KeyEvents, View};
+    use ::graphics::display::{Display, Scaling};
+    use ::graphics::queue::RenderQueue;
+    use ::gameobjects::camera::play_area;
+    use ::gameobjects::main_menu::main_menu;
+    use ::gameobjects::player::ShipViewBuilder;
+
+    use std::path::Path;
+    use sdl2::pixels::Color;
+
+    #[test]
+    fn menu_draws_through_a_recording_backend() {
+        let display = Display::new(Scaling::Letterbox);
+        let mut backend = RecordingBackend::new(display.logical_size);
+        let mut menu = main_menu(&mut backend, box ShipViewBuilder);
+        let mut queue = RenderQueue::new();
+
+        {
+            let mut context = Context {
+                events: KeyEvents::new(Keys::default(), Keys::default()),
+                renderer: &mut backend,
+                objects: Default::default(),
+                screen: display.screen(),
+                play_area: play_area(),
+            };
+
+            match menu.update(&mut context, 16) {
+                Action::Render(sprites) => queue.extend(sprites),
+                _ => panic!("The menu didn't render anything"),
+            }
+
+            queue.flush(context.renderer);
+        }
+
+        backend.present();
+
+        let commands = backend.take_commands();
+
+        assert_eq!(
+            commands.first(),
+            Some(&DrawCommand::Clear(Color::RGB(0, 0, 0)))
+        );
+        assert_eq!(commands.last(), Some(&DrawCommand::Present));
+        assert!(
+            commands.iter().any(|c| match *c {
+                DrawCommand::Sprite {
+                    texture: TextureSource::Image(ref path), ..
+                } => path == Path::new("assets/spaceBG.png"),
+                _ => false,
+            })
+        );
+
+        let labels = commands.iter().filter_map(|c| match *c {
+            DrawCommand::Sprite {
+                texture: TextureSource::Text(ref text),
+                mask,
+                ..
+            } => Some((text.as_str(), mask.height)),
+            _ => None,
+        }).collect::<Vec<_>>();
+
+        // The first item starts off selected, which draws it bigger
+        assert_eq!(labels, vec![("Play", 38), ("Quit", 32)]);
+        assert_eq!(backend.frames, 1);
+    }
+}
@@ -0,0 +1,286 @@
+use ::events::Keys;
+use ::view::{Action, Context, View};
+use ::graphics::display::Display;
+use ::graphics::queue::RenderQueue;
+use ::graphics::sprites::VisibleComponent;
+use ::graphics::backend::Texture;
+
+use std::cmp;
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use chrono::UTC;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::Renderer;
+use sdl2::surface::Surface;
+use sdl2_image::SaveSurface;
+
+/// Where screenshots go, unless asked otherwise.
+pub const SCREENSHOT_DIR: &'static str = "screenshots";
+
+/// The pixels of a whole frame, as RGB with no padding between rows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Reads back everything that's been drawn since the last present, in
+    /// the renderer's current viewport, which should be `size` pixels big.
+    pub fn read(renderer: &Renderer, size: (u32, u32)) -> Result<Self, String> {
+        let (width, height) = size;
+        let pixels = try!(renderer.read_pixels(None, PixelFormatEnum::RGB24));
+
+        if pixels.len() != (width * height * 3) as usize {
+            return Err(
+                format!(
+                    "Read {} bytes of pixels for a {}x{} frame",
+                    pixels.len(),
+                    width,
+                    height
+                )
+            );
+        }
+
+        Ok(
+            Frame {
+                width: width,
+                height: height,
+                pixels: pixels,
+            }
+        )
+    }
+
+    pub fn save_png<P: AsRef<Path>>(
+        &mut self,
+        path: P
+    ) -> Result<(), String> {
+        let (width, height) = (self.width, self.height);
+        let surface = try!(
+            Surface::from_data(
+                &mut self.pixels,
+                width,
+                height,
+                width * 3,
+                PixelFormatEnum::RGB24
+            )
+        );
+
+        surface.save(path.as_ref())
+    }
+}
+
+/// Where golden snapshots are kept.
+pub const GOLDEN_DIR: &'static str = "tests/golden";
+
+/// A path in `dir` named after the current time, for a new screenshot.
+pub fn screenshot_path<P: AsRef<Path>>(dir: P) -> PathBuf {
+    dir.as_ref().join(
+        UTC::now().format("screenshot-%Y%m%d-%H%M%S%.3f.png").to_string()
+    )
+}
+
+/// Captures the current frame into a new file in `dir`, returning the path
+/// it was saved to.
+pub fn screenshot<P: AsRef<Path>>(
+    renderer: &mut Renderer,
+    display: &Display,
+    dir: P
+) -> Result<PathBuf, String> {
+    try!(fs::create_dir_all(dir.as_ref()).map_err(|e| e.to_string()));
+
+    let path = screenshot_path(dir);
+    let mut frame = try!(display.capture(renderer));
+
+    try!(frame.save_png(&path));
+
+    Ok(path)
+}
+
+/// Saves every `every`th frame into a directory, numbered in order, for
+/// turning into videos later.
+pub struct FrameDump {
+    pub dir: PathBuf,
+    pub every: u32,
+    frames_seen: u32,
+    frames_saved: u32,
+}
+
+impl FrameDump {
+    pub fn new<P: Into<PathBuf>>(dir: P, every: u32) -> Result<Self, String> {
+        let dir = dir.into();
+
+        try!(fs::create_dir_all(&dir).map_err(|e| e.to_string()));
+
+        Ok(
+            FrameDump {
+                dir: dir,
+                every: if every == 0 { 1 } else { every },
+                frames_seen: 0,
+                frames_saved: 0,
+            }
+        )
+    }
+
+    /// Counts a frame, saving it if it's due. Should be called once a frame,
+    /// after drawing and before presenting.
+    pub fn frame(
+        &mut self,
+        renderer: &mut Renderer,
+        display: &Display
+    ) -> Result<(), String> {
+        let due = self.frames_seen % self.every == 0;
+
+        self.frames_seen += 1;
+
+        if !due { return Ok(()); }
+
+        let path = self.dir.join(format!("frame-{:06}.png", self.frames_saved));
+        let mut frame = try!(display.capture(renderer));
+
+        self.frames_saved += 1;
+
+        frame.save_png(path)
+    }
+}
+
+/// Updates `view` by `elapsed` and draws whatever it renders, without
+/// presenting it. With a `RecordingBackend` this renders views without a
+/// window, so that their snapshots can be checked.
+pub fn render_view(
+    view: &mut View<Keys, VisibleComponent<Texture>>,
+    context: &mut Context<Keys>,
+    elapsed: u32
+) -> Result<(), String> {
+    let mut queue = RenderQueue::new();
+
+    match view.update(context, elapsed) {
+        Action::Render(sprites) => queue.extend(sprites),
+        _ => return Err("The view didn't render anything".to_string()),
+    }
+
+    queue.flush(context.renderer);
+
+    Ok(())
+}
+
+/// The lines of `snapshot` that are different to those of `golden`,
+/// counting from 1. A line that only one of them has is different too.
+pub fn snapshot_differences(golden: &str, snapshot: &str) -> Vec<usize> {
+    let golden = golden.lines().collect::<Vec<_>>();
+    let snapshot = snapshot.lines().collect::<Vec<_>>();
+
+    (0..cmp::max(golden.len(), snapshot.len()))
+        .filter(|&i| golden.get(i) != snapshot.get(i))
+        .map(|i| i + 1)
+        .collect()
+}
+
+/// Checks `snapshot` against the golden snapshot called `name`, returning
+/// the lines that differ. If there isn't one yet, or `UPDATE_GOLDEN` is
+/// set, `snapshot` becomes the golden one instead.
+pub fn check_golden(name: &str, snapshot: &str) -> Result<Vec<usize>, String> {
+    let path = Path::new(GOLDEN_DIR).join(format!("{}.txt", name));
+
+    if path.exists() && env::var_os("UPDATE_GOLDEN").is_none() {
+        let mut golden = String::new();
+
+        try!(
+            File::open(&path)
+                .and_then(|mut file| file.read_to_string(&mut golden))
+                .map_err(|e| format!("{}: {}", path.display(), e))
+        );
+
+        return Ok(snapshot_differences(&golden, snapshot));
+    }
+
+    try!(fs::create_dir_all(GOLDEN_DIR).map_err(|e| e.to_string()));
+    try!(
+        File::create(&path)
+            .and_then(|mut file| file.write_all(snapshot.as_bytes()))
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    );
+
+    Ok(vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_golden, render_view, snapshot_differences};
+    use ::events::Keys;
+    use ::view::{Context, KeyEvents, View};
+    use ::graphics::backend::{Backend, RecordingBackend, Texture};
+    use ::graphics::display::{Display, Scaling};
+    use ::graphics::sprites::VisibleComponent;
+    use ::gameobjects::camera::play_area;
+    use ::gameobjects::main_menu::main_menu;
+    use ::gameobjects::player::ShipViewBuilder;
+
+    /// Renders a frame of `view` with `keys` held down, after a frame with
+    /// nothing held, and returns its snapshot.
+    fn render_frame(
+        backend: &mut RecordingBackend,
+        view: &mut View<Keys, VisibleComponent<Texture>>,
+        keys: Keys
+    ) -> String {
+        let screen = Display::new(Scaling::Letterbox).screen();
+
+        {
+            let mut context = Context {
+                events: KeyEvents::new(Keys::default(), keys),
+                renderer: &mut *backend,
+                objects: Default::default(),
+                screen: screen,
+                play_area: play_area(),
+            };
+
+            render_view(view, &mut context, 16).unwrap();
+        }
+
+        let snapshot = backend.snapshot();
+
+        backend.present();
+
+        snapshot
+    }
+
+    #[test]
+    fn differences_are_counted_by_line() {
+        let golden = "a\nb\nc\n";
+
+        assert!(snapshot_differences(golden, golden).is_empty());
+        assert_eq!(snapshot_differences(golden, "a\nx\nc\n"), vec![2]);
+        assert_eq!(snapshot_differences(golden, "a\nb\n"), vec![3]);
+    }
+
+    #[test]
+    fn menu_matches_its_golden_snapshot() {
+        let display = Display::new(Scaling::Letterbox);
+        let mut backend = RecordingBackend::new(display.logical_size);
+        let mut menu = main_menu(&mut backend, box ShipViewBuilder);
+
+        let first = render_frame(&mut backend, &mut menu, Keys::default());
+
+        assert_eq!(check_golden("main_menu", &first), Ok(vec![]));
+
+        // Moving the selection redraws both labels at their new sizes, but
+        // the background only scrolls by a frame
+        let moved = render_frame(
+            &mut backend,
+            &mut menu,
+            Keys { down: true, .. Keys::default() }
+        );
+        let changed = snapshot_differences(&first, &moved);
+        let labels = moved.lines()
+            .enumerate()
+            .filter(|&(_, line)| line.contains("Text("))
+            .map(|(i, _)| i + 1)
+            .collect::<Vec<_>>();
+
+        assert_eq!(labels.len(), 2);
+        assert!(labels.iter().all(|line| changed.contains(line)));
+    }
+}
@@ -0,0 +1,122 @@
+use ::gameobjects::Dest;
+use ::graphics::capture::Frame;
+
+use std::cmp;
+use sdl2::render::Renderer;
+use sdl2::video::FullscreenType;
+
+/// The size that everything is drawn at, before being scaled up to fit the
+/// window.
+pub const LOGICAL_SIZE: (u32, u32) = (800, 600);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scaling {
+    /// Scales by as much as fits, with black bars along whichever edges are
+    /// left over.
+    Letterbox,
+    /// Scales by the largest whole number that fits, so that pixels stay
+    /// square and sharp, at the cost of wider bars.
+    Integer,
+}
+
+/// Fits the logical screen to the window, however big the window is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Display {
+    pub logical_size: (u32, u32),
+    pub scaling: Scaling,
+}
+
+impl Display {
+    pub fn new(scaling: Scaling) -> Self {
+        Display {
+            logical_size: LOGICAL_SIZE,
+            scaling: scaling,
+        }
+    }
+
+    /// The logical screen, which is what views should lay themselves out in.
+    pub fn screen(&self) -> Dest {
+        let (w, h) = self.logical_size;
+
+        Dest::default().with_size(w, h)
+    }
+
+    /// How much to scale the logical screen by, and where it ends up in a
+    /// window of `output_size` pixels.
+    pub fn fit(&self, output_size: (u32, u32)) -> (f64, Dest) {
+        let (out_w, out_h) = output_size;
+        let (w, h) = self.logical_size;
+
+        let scale = (out_w as f64 / w as f64).min(out_h as f64 / h as f64);
+        let scale = match self.scaling {
+            Scaling::Letterbox => scale,
+            // Windows smaller than the logical screen still get something
+            Scaling::Integer => scale.floor().max(1.0),
+        };
+
+        let scaled_w = cmp::min((w as f64 * scale) as u32, out_w);
+        let scaled_h = cmp::min((h as f64 * scale) as u32, out_h);
+
+        (
+            scale,
+            Dest {
+                x: ((out_w - scaled_w) / 2) as _,
+                y: ((out_h - scaled_h) / 2) as _,
+                width: scaled_w,
+                height: scaled_h,
+            },
+        )
+    }
+
+    /// Sets up `renderer` so that drawing to the logical screen fills the
+    /// middle of the window. Should be called again whenever the window
+    /// changes size.
+    pub fn apply(&self, renderer: &mut Renderer) -> Result<(), String> {
+        let output_size = try!(renderer.output_size());
+        let (scale, viewport) = self.fit(output_size);
+
+        // The viewport is given in scaled coordinates, so it has to be set
+        // before the scale is
+        try!(renderer.set_scale(1.0, 1.0));
+        renderer.set_viewport(Some(viewport.into()));
+
+        renderer.set_scale(scale as f32, scale as f32)
+    }
+
+    /// Reads back the whole window as it's been drawn so far this frame,
+    /// black bars and all.
+    pub fn capture(&self, renderer: &mut Renderer) -> Result<Frame, String> {
+        let size = try!(renderer.output_size());
+
+        try!(renderer.set_scale(1.0, 1.0));
+        renderer.set_viewport(None);
+
+        let frame = Frame::read(renderer, size);
+
+        try!(self.apply(renderer));
+
+        frame
+    }
+
+    /// Switches the window between fullscreen and windowed, and fits the
+    /// logical screen to its new size.
+    pub fn toggle_fullscreen(
+        &self,
+        renderer: &mut Renderer
+    ) -> Result<(), String> {
+        {
+            let window = try!(
+                renderer.window_mut().ok_or("Not rendering to a window")
+            );
+
+            let next = match window.fullscreen_state() {
+                FullscreenType::Off => FullscreenType::Desktop,
+                _ => FullscreenType::Off,
+            };
+
+            try!(window.set_fullscreen(next));
+        }
+
+        self.apply(renderer)
+    }
+}
@@ -0,0 +1,75 @@
+use ::gameobjects::Dest;
+
+/// The broad groups that a frame is drawn in, back to front.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    Background,
+    World,
+    Effects,
+    Hud,
+    Overlay,
+}
+
+/// Where something is drawn: by layer first, and then by depth within that
+/// layer, with higher depths in front.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DrawKey {
+    pub layer: Layer,
+    pub depth: i32,
+}
+
+impl DrawKey {
+    pub fn new(layer: Layer, depth: i32) -> Self {
+        DrawKey {
+            layer: layer,
+            depth: depth,
+        }
+    }
+}
+
+impl From<Layer> for DrawKey {
+    fn from(layer: Layer) -> Self {
+        DrawKey::new(layer, 0)
+    }
+}
+
+/// Everything to be drawn in a frame, collected in any order and then
+/// sorted by `DrawKey`. Things with the same key keep the order they were
+/// added in.
+pub struct DrawList<R> {
+    items: Vec<(DrawKey, R, Dest)>,
+}
+
+impl<R> Default for DrawList<R> {
+    fn default() -> Self {
+        DrawList {
+            items: vec![],
+        }
+    }
+}
+
+impl<R> DrawList<R> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn extend<K, I>(&mut self, key: K, sprites: I)
+        where K: Into<DrawKey>, I: IntoIterator<Item=(R, Dest)>
+    {
+        let key = key.into();
+
+        self.items.extend(
+            sprites.into_iter().map(|(sprite, dest)| (key, sprite, dest))
+        );
+    }
+
+    /// Everything in the list, in the order it should be drawn.
+    pub fn into_sorted(mut self) -> Vec<(R, Dest)> {
+        // `sort_by_key` is stable, which is what keeps ties in order
+        self.items.sort_by_key(|&(key, _, _)| key);
+
+        self.items.into_iter()
+            .map(|(_, sprite, dest)| (sprite, dest))
+            .collect()
+    }
+}
@@ -1,4 +1,10 @@
 pub mod sprites;
 pub mod font_cache;
+pub mod alpha_mask;
+pub mod display;
+pub mod layers;
+pub mod queue;
+pub mod backend;
+pub mod capture;
 
 pub use self::sprites::*;
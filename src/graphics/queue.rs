@@ -0,0 +1,141 @@
+use ::gameobjects::Dest;
+use ::graphics::sprites::VisibleComponent;
+use ::graphics::backend::{Backend, Texture};
+
+/// How far back a draw can be moved to join a batch that uses the same
+/// texture. Looking further saves more texture switches, but the cost of
+/// looking grows with every draw in the frame.
+const MAX_LOOKBACK: usize = 8;
+
+/// What was drawn in a frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DrawStats {
+    /// Everything that was queued.
+    pub draws: usize,
+    /// How many runs of draws from the same texture there were, with
+    /// rectangles counting as one texture of their own.
+    pub batches: usize,
+    /// How many of those batches used a different texture to the one before.
+    pub texture_switches: usize,
+}
+
+/// Which texture a draw uses, as far as batching is concerned.
+fn texture_of(comp: &VisibleComponent<Texture>) -> Option<*const Texture> {
+    match *comp {
+        VisibleComponent::Sprite(ref s) |
+        VisibleComponent::Transformed(ref s, _) => Some(&*s.texture as _),
+        VisibleComponent::Rectangle(_) => None,
+    }
+}
+
+fn overlaps(a: &Dest, b: &Dest) -> bool {
+    a.left() < b.right() && b.left() < a.right() &&
+        a.top() < b.bottom() && b.top() < a.bottom()
+}
+
+struct Batch {
+    texture: Option<*const Texture>,
+    draws: Vec<(VisibleComponent<Texture>, Dest)>,
+}
+
+/// Collects everything drawn in a frame, so that it can be drawn all at once
+/// with draws from the same texture grouped together. Draws are only ever
+/// moved back past draws that they don't overlap, so the frame looks the
+/// same either way.
+pub struct RenderQueue {
+    batches: Vec<Batch>,
+    last_stats: DrawStats,
+}
+
+impl Default for RenderQueue {
+    fn default() -> Self {
+        RenderQueue {
+            batches: vec![],
+            last_stats: Default::default(),
+        }
+    }
+}
+
+impl RenderQueue {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The batch that a draw from `texture` covering `dest` can go into, if
+    /// there's one close enough that it can be moved to.
+    fn batch_for(
+        &self,
+        texture: Option<*const Texture>,
+        dest: &Dest
+    ) -> Option<usize> {
+        let len = self.batches.len();
+
+        for i in (0..len).rev().take(MAX_LOOKBACK) {
+            let batch = &self.batches[i];
+
+            if batch.texture == texture {
+                return Some(i);
+            }
+
+            if batch.draws.iter().any(|&(_, ref d)| overlaps(d, dest)) {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    pub fn push(&mut self, sprite: VisibleComponent<Texture>, dest: Dest) {
+        let texture = texture_of(&sprite);
+
+        match self.batch_for(texture, &dest) {
+            Some(i) => self.batches[i].draws.push((sprite, dest)),
+            None => self.batches.push(
+                Batch {
+                    texture: texture,
+                    draws: vec![(sprite, dest)],
+                }
+            ),
+        }
+    }
+
+    /// Draws everything queued since the last flush, and empties the queue.
+    pub fn flush(&mut self, backend: &mut Backend) -> DrawStats {
+        let mut stats = DrawStats::default();
+        let mut current = None;
+
+        for batch in self.batches.drain(..) {
+            stats.batches += 1;
+
+            if batch.texture.is_some() && batch.texture != current {
+                stats.texture_switches += 1;
+                current = batch.texture;
+            }
+
+            for (sprite, dest) in batch.draws {
+                stats.draws += 1;
+
+                backend.draw(&sprite, dest);
+            }
+        }
+
+        self.last_stats = stats;
+
+        stats
+    }
+
+    /// What was drawn by the last flush.
+    pub fn stats(&self) -> DrawStats {
+        self.last_stats
+    }
+}
+
+impl Extend<(VisibleComponent<Texture>, Dest)> for RenderQueue {
+    fn extend<I>(&mut self, iter: I)
+        where I: IntoIterator<Item=(VisibleComponent<Texture>, Dest)>
+    {
+        for (sprite, dest) in iter {
+            self.push(sprite, dest);
+        }
+    }
+}
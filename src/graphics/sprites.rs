@@ -1,14 +1,15 @@
 use ::gameobjects::{Clip, Dest};
 use ::time::TimeExtensions;
+use ::graphics::backend::{Backend, Texture};
 
 use std::rc::Rc;
 use std::ops::Index;
 use std::hash::Hash;
 use std::path::Path;
 use std::collections::HashMap;
-use sdl2::render::{TextureQuery, Texture, Renderer};
+use sdl2::render::{BlendMode, Texture as SdlTexture, Renderer};
+use sdl2::rect::Point;
 use sdl2::pixels::Color;
-use sdl2_image::LoadTexture;
 use itertools::*;
 
 pub trait GetSize {
@@ -19,14 +20,72 @@ pub trait CopyRenderable<T> {
     fn copy_renderable(&mut self, sprite: &T, dest: Dest);
 }
 
+/// The SDL texture inside `texture`, if it was made by an `SdlBackend`.
+fn sdl_texture(texture: &Texture) -> Option<&SdlTexture> {
+    texture.downcast_ref()
+}
+
 impl<'a> CopyRenderable<Sprite<Texture>> for Renderer<'a> {
     fn copy_renderable(&mut self, sprite: &Sprite<Texture>, dest: Dest) {
-        self.copy(
-            &sprite.texture,
-            Some(sprite.mask.into()),
-            Some(dest.into())
-        );
+        if let Some(texture) = sdl_texture(&sprite.texture) {
+            self.copy(texture, Some(sprite.mask.into()), Some(dest.into()));
+        }
+    }
+}
+
+/// Sets the colour, alpha and blend modulation of `texture`. Textures are
+/// shared between sprites behind an `Rc`, so this goes through SDL directly
+/// rather than needing a `&mut Texture`, and it should be put back the way it
+/// was after drawing.
+fn modulate(texture: &SdlTexture, transform: &Transform) {
+    use sdl2::sys::render as ll;
+
+    let (r, g, b) = transform.tint.rgb();
+
+    unsafe {
+        let raw = texture.raw();
+
+        ll::SDL_SetTextureColorMod(raw, r, g, b);
+        ll::SDL_SetTextureAlphaMod(raw, transform.alpha);
+        ll::SDL_SetTextureBlendMode(raw, transform.blend as _);
+    }
+}
+
+fn copy_transformed(
+    renderer: &mut Renderer,
+    sprite: &Sprite<Texture>,
+    transform: &Transform,
+    dest: Dest
+) {
+    if *transform == Transform::default() {
+        return renderer.copy_renderable(sprite, dest);
     }
+
+    let texture = if let Some(t) = sdl_texture(&sprite.texture) {
+        t
+    } else {
+        return;
+    };
+
+    // Put back afterwards, since other sprites share the texture
+    let original = Transform {
+        blend: texture.blend_mode(),
+        .. Transform::default()
+    };
+
+    modulate(texture, transform);
+
+    renderer.copy_ex(
+        texture,
+        Some(sprite.mask.into()),
+        Some(dest.into()),
+        transform.angle,
+        transform.pivot.map(|(x, y)| Point::new(x, y)),
+        transform.flip_h,
+        transform.flip_v
+    );
+
+    modulate(texture, &original);
 }
 
 impl<'a> CopyRenderable<VisibleRect> for Renderer<'a> {
@@ -35,6 +94,9 @@ impl<'a> CopyRenderable<VisibleRect> for Renderer<'a> {
         &VisibleRect(color): &VisibleRect,
         dest: Dest
     ) {
+        // Lets translucent colours, like those of fading particles, blend
+        // with what's behind them
+        self.set_blend_mode(BlendMode::Blend);
         self.set_draw_color(color);
 
         self.fill_rect(dest.into()).unwrap();
@@ -50,6 +112,8 @@ impl<'a> CopyRenderable<VisibleComponent<Texture>> for Renderer<'a> {
         match *comp {
             VisibleComponent::Sprite(ref spr) =>
                 self.copy_renderable(spr, dest),
+            VisibleComponent::Transformed(ref spr, ref transform) =>
+                copy_transformed(self, spr, transform, dest),
             VisibleComponent::Rectangle(ref rect) =>
                 self.copy_renderable(rect, dest),
         }
@@ -66,14 +130,6 @@ impl<'a, T> Renderable<Renderer<'a>> for T where Renderer<'a>: CopyRenderable<T>
     }
 }
 
-impl GetSize for Texture {
-    fn size(&self) -> [u32; 2] {
-        let TextureQuery { width: w, height: h, .. } = self.query();
-
-        [w, h]
-    }
-}
-
 impl GetSize for Clip {
     fn size(&self) -> [u32; 2] {
         [self.width, self.height]
@@ -112,11 +168,82 @@ pub struct Sprite<T: GetSize> {
 
 pub struct VisibleRect(pub Color);
 
+/// How a sprite is changed as it's drawn, on top of being stretched to its
+/// destination.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    /// Clockwise, in degrees.
+    pub angle: f64,
+    /// What the sprite is rotated around, relative to the top left of its
+    /// destination. The middle of the destination if there isn't one.
+    pub pivot: Option<(i32, i32)>,
+    pub flip_h: bool,
+    pub flip_v: bool,
+    pub alpha: u8,
+    /// Multiplied with the sprite's own colours, so white leaves it as it is.
+    pub tint: Color,
+    pub blend: BlendMode,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform {
+            angle: 0.0,
+            pivot: None,
+            flip_h: false,
+            flip_v: false,
+            alpha: 255,
+            tint: Color::RGB(255, 255, 255),
+            blend: BlendMode::Blend,
+        }
+    }
+}
+
 pub enum VisibleComponent<T: GetSize> {
     Sprite(Sprite<T>),
+    Transformed(Sprite<T>, Transform),
     Rectangle(VisibleRect),
 }
 
+impl<T: GetSize> VisibleComponent<T> {
+    /// Changes how this is drawn with `f`. Rectangles only take on the
+    /// transform's alpha, since they're a flat colour anyway.
+    pub fn transform<F: FnOnce(&mut Transform)>(self, f: F) -> Self {
+        match self {
+            VisibleComponent::Sprite(sprite) => {
+                let mut transform = Transform::default();
+
+                f(&mut transform);
+
+                VisibleComponent::Transformed(sprite, transform)
+            },
+            VisibleComponent::Transformed(sprite, mut transform) => {
+                f(&mut transform);
+
+                VisibleComponent::Transformed(sprite, transform)
+            },
+            VisibleComponent::Rectangle(VisibleRect(color)) => {
+                let mut transform = Transform::default();
+
+                f(&mut transform);
+
+                let (r, g, b, a) = color.rgba();
+                let alpha = a as u32 * transform.alpha as u32 / 255;
+
+                VisibleRect(Color::RGBA(r, g, b, alpha as u8)).into()
+            },
+        }
+    }
+
+    pub fn rotated(self, angle: f64) -> Self {
+        self.transform(|t| t.angle += angle)
+    }
+
+    pub fn tinted(self, tint: Color) -> Self {
+        self.transform(|t| t.tint = tint)
+    }
+}
+
 impl<T: GetSize> From<Sprite<T>> for VisibleComponent<T> {
     fn from(sprite: Sprite<T>) -> Self {
         VisibleComponent::Sprite(sprite)
@@ -170,14 +297,17 @@ impl<T: GetSize> Sprite<T> {
 }
 
 pub trait LoadSprite<T: GetSize> {
-    fn load_sprite<P: AsRef<Path>>(&self, path: P) -> Result<Sprite<T>, String>;
+    fn load_sprite<P: AsRef<Path>>(
+        &mut self,
+        path: P
+    ) -> Result<Sprite<T>, String>;
 }
 
-impl<L: LoadTexture> LoadSprite<Texture> for L {
+impl<'a> LoadSprite<Texture> for Backend + 'a {
     fn load_sprite<P: AsRef<Path>>(
-        &self, path: P
+        &mut self, path: P
     ) -> Result<Sprite<Texture>, String> {
-        self.load_texture(path.as_ref()).map(Sprite::new)
+        self.load_image(path.as_ref()).map(Sprite::new)
     }
 }
 
@@ -297,7 +427,7 @@ impl<
         &self.sprites[self.frame_index(now)]
     }
 
-    fn frame_index(&self, now: Time) -> AIdx {
+    pub fn frame_index(&self, now: Time) -> AIdx {
         let anim = &self.animations.0[&self.current.1];
 
         let tick_diff =
@@ -25,39 +25,65 @@ mod coalesce;
 mod split_iterator;
 mod fixed_size_iter;
 
-use graphics::font_cache::FontCache;
-use graphics::sprites::CopyRenderable;
+use graphics::backend::{Backend, SdlBackend};
+use graphics::display::{Display, Scaling, LOGICAL_SIZE};
+use graphics::queue::RenderQueue;
+use graphics::capture::{self, FrameDump, SCREENSHOT_DIR};
 use gameobjects::player::*;
 use gameobjects::main_menu::main_menu;
+use gameobjects::camera::play_area;
 use events::*;
 use view::*;
 use chrono::{UTC, Duration};
 
+const TITLE: &'static str = "Test game";
+/// Where frames are saved while recording, and how often.
+const RECORDING_DIR: &'static str = "recording";
+const RECORD_EVERY: u32 = 2;
+
+/// Reports something that went wrong without stopping the game.
+fn report<T>(result: Result<T, String>, what: &str) -> Option<T> {
+    use std::io::{self, Write};
+
+    result.map_err(|e| {
+        let _ = writeln!(io::stderr(), "{}: {}", what, e);
+    }).ok()
+}
+
 fn main() {
     let sdl = sdl2::init().unwrap();
     let sdl_ttf = sdl2_ttf::init().unwrap();
     let video = sdl.video().unwrap();
 
+    let (width, height) = LOGICAL_SIZE;
+
     let window = video
-        .window("Test game", 800, 600)
+        .window(TITLE, width, height)
         .position_centered()
+        .resizable()
         .opengl()
         .build()
         .unwrap();
 
-    let mut renderer = window.renderer()
+    let renderer = window.renderer()
         .accelerated()
         .build()
         .unwrap();
+    let mut backend = SdlBackend::new(renderer, &sdl_ttf);
+
+    let display = Display::new(Scaling::Letterbox);
+
+    display.apply(&mut backend.renderer).unwrap();
 
     let mut events = EventStream::new(sdl.event_pump().unwrap());
     let mut keys = Keys::default();
 
-    let mut font_cache = FontCache::new(&sdl_ttf);
+    let mut queue = RenderQueue::new();
+    let mut show_stats = false;
+    let mut recording: Option<FrameDump> = None;
 
     let mut state = box main_menu(
-        &mut renderer,
-        &mut font_cache,
+        &mut backend,
         box ShipViewBuilder
     ) as Box<View<_, _>>;
     let mut time = UTC::now();
@@ -70,32 +96,44 @@ fn main() {
         let elapsed_ms = elapsed.num_milliseconds() as u32;
 
         let new_keys = events.pump(&keys);
+        let key_events = KeyEvents::new(keys.clone(), new_keys.clone());
+
+        if key_events.down.quit { break; }
+
+        if events.take_resized() {
+            display.apply(&mut backend.renderer).unwrap();
+        }
+
+        if key_events.pressed.fullscreen {
+            display.toggle_fullscreen(&mut backend.renderer).unwrap();
+        }
+
+        if key_events.pressed.draw_stats {
+            show_stats = !show_stats;
+
+            if let Some(window) = backend.renderer.window_mut() {
+                window.set_title(TITLE);
+            }
+        }
 
         {
             let mut context =
                 Context {
-                    events: KeyEvents::new(
-                        keys.clone(),
-                        new_keys.clone(),
-                    ),
-                    renderer: &mut renderer,
-                    font_cache: &mut font_cache,
+                    events: key_events.clone(),
+                    renderer: &mut backend,
+                    objects: Default::default(),
+                    screen: display.screen(),
+                    play_area: play_area(),
                 };
 
-            if context.events.down.quit { break; }
-
             let next_state = match state.update(&mut context, elapsed_ms) {
                 Action::Quit =>
                     break,
                 Action::ChangeView(next) =>
                     Some(next.build_view(&mut context)),
                 Action::Render(vec) => {
-                    for (sprite, dest) in vec {
-                        context.renderer.copy_renderable(
-                            &sprite,
-                            dest
-                        );
-                    }
+                    queue.extend(vec);
+                    queue.flush(context.renderer);
 
                     None
                 },
@@ -106,7 +144,56 @@ fn main() {
             }
         }
 
-        renderer.present();
+        if key_events.pressed.screenshot {
+            report(
+                capture::screenshot(
+                    &mut backend.renderer,
+                    &display,
+                    SCREENSHOT_DIR
+                ),
+                "Couldn't take a screenshot"
+            );
+        }
+
+        if key_events.pressed.record {
+            recording = match recording {
+                Some(_) => None,
+                None => report(
+                    FrameDump::new(RECORDING_DIR, RECORD_EVERY),
+                    "Couldn't start recording"
+                ),
+            };
+        }
+
+        // A frame that can't be saved probably means none of them can, so
+        // recording stops rather than failing every frame
+        let failed = recording.as_mut().map_or(false, |dump|
+            report(
+                dump.frame(&mut backend.renderer, &display),
+                "Couldn't save a frame, so recording has stopped"
+            ).is_none()
+        );
+
+        if failed {
+            recording = None;
+        }
+
+        backend.present();
+
+        if show_stats {
+            let stats = queue.stats();
+            let title = format!(
+                "{} - {} draws, {} batches, {} texture switches",
+                TITLE,
+                stats.draws,
+                stats.batches,
+                stats.texture_switches
+            );
+
+            if let Some(window) = backend.renderer.window_mut() {
+                window.set_title(&title);
+            }
+        }
 
         if elapsed < target_ms_per_frame {
             use std::thread;
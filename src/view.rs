@@ -1,11 +1,9 @@
 use ::events::KeySet;
-use ::graphics::font_cache::FontCache;
-use ::graphics::sprites::Renderable;
-use ::gameobjects::Dest;
+use ::graphics::backend::Backend;
+use ::gameobjects::{BoundingRect, Dest};
+use ::gameobjects::object_table::ObjectTable;
 
-use sdl2::render::Renderer;
-
-pub enum Action<'a, T: KeySet, R: Renderable<Renderer<'a>>> {
+pub enum Action<'a, T: KeySet, R: 'a> {
     Quit,
     ChangeView(Box<ViewBuilder<T, R>>),
     Render(Box<Iterator<Item=(R, Dest)> + 'a>),
@@ -33,11 +31,20 @@ impl<T: KeySet> KeyEvents<T> {
 
 pub struct Context<'a, 'b: 'a, T: KeySet> {
     pub events: KeyEvents<T>,
-    pub renderer: &'a mut Renderer<'b>,
-    pub font_cache: &'a mut FontCache<'b>,
+    /// Whatever's being drawn with. Views and objects should only load
+    /// textures and draw through this, so that they don't care which it is.
+    pub renderer: &'a mut (Backend + 'b),
+    /// The objects of the current view, for views that have any.
+    pub objects: ObjectTable,
+    /// The logical screen, which is scaled to fit the window. Views lay
+    /// themselves out in this rather than the window's real size.
+    pub screen: Dest,
+    /// Where gameplay happens, in world coordinates. Objects should use this
+    /// rather than the size of the window.
+    pub play_area: BoundingRect,
 }
 
-pub trait View<T: KeySet, R: for<'a> Renderable<Renderer<'a>>> {
+pub trait View<T: KeySet, R> {
     fn update(
         &mut self,
         context: &mut Context<T>,
@@ -46,14 +53,14 @@ pub trait View<T: KeySet, R: for<'a> Renderable<Renderer<'a>>> {
 }
 
 #[allow(boxed_local)]
-pub trait ViewBuilder<T: KeySet, R: for<'a> Renderable<Renderer<'a>>> {
+pub trait ViewBuilder<T: KeySet, R> {
     fn build_view(self: Box<Self>, context: &mut Context<T>) -> Box<View<T, R>>;
 }
 
 #[allow(boxed_local)]
 impl<
     T: KeySet,
-    R: for<'a> Renderable<Renderer<'a>>,
+    R,
     F: FnOnce(&mut Context<T>) -> Box<View<T, R>>
 > ViewBuilder<T, R> for F {
     fn build_view(